@@ -0,0 +1,70 @@
+//! API activity
+
+use crate::error::Error;
+use crate::{
+    client::{ConnectRequest, HTTPClient},
+    models::activity::ApiRequest,
+};
+
+/// Get a page of the API requests made to the server, most recent first
+///
+/// # Fields
+///
+/// - `limit`: the maximum number of requests to return.
+/// - `offset`: the number of requests to skip, use this to fetch subsequent pages.
+pub async fn all(
+    client: &impl HTTPClient,
+    limit: u32,
+    offset: u32,
+) -> Result<(Vec<ApiRequest>, serde_json::Value), Error> {
+    let limit = limit.to_string();
+    let offset = offset.to_string();
+    let request = ConnectRequest::get(&["v1", "activity"])
+        .query("limit", &limit)
+        .query("offset", &offset);
+
+    let result = client.send_request::<Vec<ApiRequest>>(request).await?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        activity, get_test_client,
+        models::activity::{ApiRequestAction, ApiRequestResult},
+        vaults,
+    };
+    use tokio::test;
+
+    #[test]
+    async fn all() {
+        let (client, _test_vault_id) = get_test_client();
+
+        // Make sure there is at least one request logged
+        vaults::all(&client).await.unwrap();
+
+        let (requests, _) = activity::all(&client, 10, 0).await.unwrap();
+
+        assert!(!requests.is_empty());
+        assert!(requests.len() <= 10);
+        assert_eq!(requests[0].action, ApiRequestAction::Read);
+        assert_eq!(requests[0].result, ApiRequestResult::Success);
+        assert!(!requests[0].request_id.is_empty());
+    }
+
+    #[test]
+    async fn all_paged() {
+        let (client, _test_vault_id) = get_test_client();
+
+        vaults::all(&client).await.unwrap();
+        vaults::all(&client).await.unwrap();
+
+        let (first, _) = activity::all(&client, 1, 0).await.unwrap();
+        let (second, _) = activity::all(&client, 1, 1).await.unwrap();
+
+        assert_eq!(first.len(), 1);
+        assert_eq!(second.len(), 1);
+        assert_ne!(first[0].request_id, second[0].request_id);
+    }
+}
@@ -0,0 +1,429 @@
+//! `connect-1password`, a command-line client for 1Password Connect
+//!
+//! The server and token are read from `OP_SERVER_URL` and `OP_API_TOKEN`, which may also be set
+//! in a `.env` file. Vaults and items are given by their IDs.
+//!
+//! # Exit codes
+//!
+//! Failures exit with a code from `sysexits.h`, derived from the [`ErrorKind`]:
+//!
+//! - `64`: invalid command-line arguments, or the request was rejected as invalid
+//! - `65`: the response or input could not be decoded
+//! - `66`: the vault, item or field was not found
+//! - `69`: the server could not be reached
+//! - `70`: the server failed, or any other error
+//! - `74`: a local file could not be read or written
+//! - `75`: the request timed out
+//! - `77`: the token was rejected
+//! - `78`: the environment is not configured
+//!
+//! `run` exits with the code of the command it spawned.
+
+mod output;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use connect_1password::{
+    client::{Client, ClientBuilder},
+    error::{Error, ErrorKind},
+    items,
+    models::item::FullItem,
+    secrets::{self, InjectMode},
+    vaults,
+};
+use http::StatusCode;
+use std::{
+    error::Error as StdError,
+    io::{Read, Write},
+    path::PathBuf,
+    process::{Command, ExitCode},
+};
+
+type Result<T> = std::result::Result<T, Box<dyn StdError + Send + Sync>>;
+
+/// Command-line client for 1Password Connect
+#[derive(Debug, Parser)]
+#[command(name = "connect-1password", version)]
+struct Cli {
+    /// How results are printed
+    #[arg(long, value_enum, global = true, default_value_t = Format::Table)]
+    format: Format,
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Aligned columns
+    Table,
+    /// The JSON returned by Connect
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    /// Manage vaults
+    #[command(subcommand)]
+    Vaults(VaultCommands),
+    /// Manage items
+    #[command(subcommand)]
+    Items(ItemCommands),
+    /// Print the value of an `op://vault/item/[section/]field` reference
+    Read {
+        /// The secret reference
+        reference: String,
+    },
+    /// Replace `{{ op://... }}` placeholders in a template
+    Inject(InjectArgs),
+    /// Run a command with `op://` references in its environment resolved
+    Run(RunArgs),
+}
+
+#[derive(Debug, Subcommand)]
+enum VaultCommands {
+    /// List all vaults
+    List,
+    /// Show a vault
+    Get {
+        /// The vault ID
+        vault: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ItemCommands {
+    /// List the items of a vault
+    List {
+        /// The vault ID
+        vault: String,
+    },
+    /// Show an item and its fields
+    Get {
+        /// The vault ID
+        vault: String,
+        /// The item ID
+        item: String,
+        /// Show secret values, i.e. passwords and one-time passwords
+        #[arg(long)]
+        reveal: bool,
+    },
+    /// Create an item from its JSON, as returned by `items get --format json`
+    Create {
+        /// The vault ID
+        vault: String,
+        /// The file to read the item from, defaults to stdin
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Replace an item with its JSON, as returned by `items get --format json`
+    Update {
+        /// The vault ID
+        vault: String,
+        /// The item ID
+        item: String,
+        /// The file to read the item from, defaults to stdin
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Delete an item
+    Delete {
+        /// The vault ID
+        vault: String,
+        /// The item ID
+        item: String,
+    },
+}
+
+#[derive(Debug, Args)]
+struct InjectArgs {
+    /// The template to render, defaults to stdin
+    #[arg(long, short)]
+    in_file: Option<PathBuf>,
+    /// Where to write the result, defaults to stdout
+    #[arg(long, short)]
+    out_file: Option<PathBuf>,
+    /// Leave placeholders which cannot be resolved untouched
+    #[arg(long)]
+    lenient: bool,
+}
+
+#[derive(Debug, Args)]
+struct RunArgs {
+    /// Also load variables from this `.env` file, may be repeated
+    #[arg(long)]
+    env_file: Vec<PathBuf>,
+    /// The command to run, and its arguments
+    #[arg(last = true, required = true)]
+    command: Vec<String>,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(err) => {
+            // Also prints `--help` and `--version`
+            let _ = err.print();
+            return ExitCode::from(usage_exit_code(&err));
+        }
+    };
+
+    match run(cli).await {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::from(exit_code(&*err))
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<ExitCode> {
+    let client = ClientBuilder::from_env()?.build()?;
+
+    match cli.command {
+        Commands::Vaults(command) => vault_command(&client, command, cli.format).await?,
+        Commands::Items(command) => item_command(&client, command, cli.format).await?,
+        Commands::Read { reference } => {
+            println!("{}", secrets::resolve(&client, &reference).await?)
+        }
+        Commands::Inject(args) => inject(&client, args).await?,
+        Commands::Run(args) => return run_command(&client, args).await,
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn vault_command(client: &Client, command: VaultCommands, format: Format) -> Result<()> {
+    match command {
+        VaultCommands::List => {
+            let (vaults, raw) = vaults::all(client).await?;
+            match format {
+                Format::Json => output::json(&raw)?,
+                Format::Table => output::vaults(&vaults),
+            }
+        }
+        VaultCommands::Get { vault } => {
+            let (vault, raw) = vaults::get(client, &vault).await?;
+            match format {
+                Format::Json => output::json(&raw)?,
+                Format::Table => output::vault(&vault),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn item_command(client: &Client, command: ItemCommands, format: Format) -> Result<()> {
+    match command {
+        ItemCommands::List { vault } => {
+            let (items, raw) = items::all(client, &vault).await?;
+            match format {
+                Format::Json => output::json(&raw)?,
+                Format::Table => output::items(&items),
+            }
+        }
+        ItemCommands::Get {
+            vault,
+            item,
+            reveal,
+        } => {
+            let (item, raw) = items::get(client, &vault, &item, false).await?;
+            match format {
+                Format::Json => output::json(&raw)?,
+                Format::Table => output::item(&item, reveal),
+            }
+        }
+        ItemCommands::Create { vault, file } => {
+            let mut item: FullItem = serde_json::from_str(&read_input(file.as_ref())?)?;
+            item.id = None;
+            item.vault.id = vault;
+
+            let (item, raw) = items::add(client, item).await?;
+            match format {
+                Format::Json => output::json(&raw)?,
+                Format::Table => println!("{}", item.id),
+            }
+        }
+        ItemCommands::Update { vault, item, file } => {
+            let mut full_item: FullItem = serde_json::from_str(&read_input(file.as_ref())?)?;
+            full_item.id = Some(item);
+            full_item.vault.id = vault;
+
+            let (item, raw) = items::update(client, full_item).await?;
+            match format {
+                Format::Json => output::json(&raw)?,
+                Format::Table => println!("{}", item.id),
+            }
+        }
+        ItemCommands::Delete { vault, item } => items::remove(client, &vault, &item).await?,
+    }
+
+    Ok(())
+}
+
+async fn inject(client: &Client, args: InjectArgs) -> Result<()> {
+    let template = read_input(args.in_file.as_ref())?;
+    let mode = if args.lenient {
+        InjectMode::Lenient
+    } else {
+        InjectMode::Strict
+    };
+
+    let rendered = secrets::inject(client, &template, mode).await?;
+
+    match args.out_file {
+        Some(path) => std::fs::write(path, rendered)?,
+        None => std::io::stdout().write_all(rendered.as_bytes())?,
+    }
+
+    Ok(())
+}
+
+async fn run_command(client: &Client, args: RunArgs) -> Result<ExitCode> {
+    // Variables which are not valid UTF-8 cannot be references, the command inherits them as is
+    let vars = std::env::vars_os()
+        .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)));
+    let mut env = secrets::resolve_env(client, vars).await?;
+    for path in &args.env_file {
+        env.extend(secrets::env_from_path(client, path).await?);
+    }
+
+    let (program, arguments) = args.command.split_first().expect("clap requires a command");
+    let status = Command::new(program).args(arguments).envs(env).status()?;
+
+    Ok(match status.code() {
+        Some(code) => ExitCode::from(u8::try_from(code).unwrap_or(1)),
+        // Terminated by a signal
+        None => ExitCode::FAILURE,
+    })
+}
+
+/// Read a file, or stdin when no path is given
+fn read_input(path: Option<&PathBuf>) -> Result<String> {
+    let input = match path {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    };
+
+    Ok(input)
+}
+
+/// The exit code when the arguments cannot be parsed, instead of clap's `2`
+fn usage_exit_code(err: &clap::Error) -> u8 {
+    if err.use_stderr() {
+        64
+    } else {
+        0
+    }
+}
+
+/// The exit code for a failure, see the module docs
+fn exit_code(err: &(dyn StdError + 'static)) -> u8 {
+    if let Some(err) = err.downcast_ref::<Error>() {
+        return match err.kind() {
+            ErrorKind::Config => 78,
+            ErrorKind::Network => 69,
+            ErrorKind::Timeout => 75,
+            ErrorKind::Parsing => 65,
+            ErrorKind::NotFound => 66,
+            // Malformed references are invalid input, otherwise every reference is missing
+            ErrorKind::Unresolved => match err.unresolved() {
+                Some(missing) if missing.iter().all(|(_, err)| err.is_not_found()) => 66,
+                _ => 65,
+            },
+            ErrorKind::Api | ErrorKind::Http => match err.status() {
+                Some(StatusCode::NOT_FOUND) => 66,
+                Some(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) => 77,
+                Some(StatusCode::BAD_REQUEST) => 64,
+                _ => 70,
+            },
+            _ => 70,
+        };
+    }
+    if err.is::<std::io::Error>() {
+        return 74;
+    }
+    if err.is::<serde_json::Error>() {
+        return 65;
+    }
+
+    70
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{usage_exit_code, Cli};
+    use clap::{CommandFactory, Parser};
+
+    #[test]
+    fn verifies_arguments() {
+        Cli::command().debug_assert();
+
+        assert!(Cli::try_parse_from(["connect-1password", "run", "--", "env"]).is_ok());
+
+        let invalid = Cli::try_parse_from(["connect-1password", "run"]).unwrap_err();
+        let help = Cli::try_parse_from(["connect-1password", "--help"]).unwrap_err();
+        assert_eq!(usage_exit_code(&invalid), 64);
+        assert_eq!(usage_exit_code(&help), 0);
+    }
+
+    // `MockClient` needs the `testing` feature
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn maps_errors_to_exit_codes() {
+        use super::exit_code;
+        use connect_1password::{
+            error::Error,
+            secrets::{self, SecretReference},
+            testing::{Expectation, MockClient},
+            vaults,
+        };
+        use http::StatusCode;
+
+        let client = MockClient::new();
+        client
+            .expect(
+                Expectation::get(&["v1", "vaults", "a"])
+                    .respond_error(StatusCode::NOT_FOUND, "Vault a not found"),
+            )
+            .expect(
+                Expectation::get(&["v1", "vaults", "b"])
+                    .respond_error(StatusCode::UNAUTHORIZED, "Invalid token signature"),
+            )
+            .expect(
+                Expectation::get(&["v1", "vaults"])
+                    .query("filter", "name eq \"c\"")
+                    .respond_json(&serde_json::json!([])),
+            )
+            .expect(
+                Expectation::get(&["v1", "vaults"])
+                    .query("filter", "name eq \"c\"")
+                    .respond_json(&serde_json::json!([])),
+            );
+
+        let not_found = vaults::get(&client, "a").await.unwrap_err();
+        let unauthorized = vaults::get(&client, "b").await.unwrap_err();
+        let missing = secrets::resolve(&client, "op://c/d/e").await.unwrap_err();
+        let invalid: Error = "op://vault".parse::<SecretReference>().unwrap_err();
+        let io = std::io::Error::other("broken pipe");
+        let unresolved = secrets::resolve_env(&client, [("A".to_string(), "op://a".to_string())])
+            .await
+            .unwrap_err();
+        let all_missing =
+            secrets::resolve_env(&client, [("B".to_string(), "op://c/d/e".to_string())])
+                .await
+                .unwrap_err();
+
+        assert_eq!(exit_code(&not_found), 66);
+        assert_eq!(exit_code(&unauthorized), 77);
+        assert_eq!(exit_code(&missing), 66);
+        assert_eq!(exit_code(&invalid), 65);
+        assert_eq!(exit_code(&io), 74);
+        assert_eq!(exit_code(&unresolved), 65);
+        assert_eq!(exit_code(&all_missing), 66);
+    }
+}
@@ -0,0 +1,153 @@
+//! Printing results
+
+use chrono::{DateTime, Utc};
+use connect_1password::models::{
+    item::{FullItem, ItemData},
+    VaultData,
+};
+
+/// Shown instead of secret values
+const MASK: &str = "********";
+
+/// Print the JSON returned by Connect
+pub fn json(raw: &serde_json::Value) -> serde_json::Result<()> {
+    println!("{}", serde_json::to_string_pretty(raw)?);
+
+    Ok(())
+}
+
+pub fn vaults(vaults: &[VaultData]) {
+    let rows = vaults
+        .iter()
+        .map(|vault| {
+            vec![
+                vault.id.clone(),
+                vault.name.clone(),
+                date(vault.updated_at.as_ref()),
+            ]
+        })
+        .collect();
+
+    print!("{}", table(&["ID", "NAME", "UPDATED"], rows));
+}
+
+pub fn vault(vault: &VaultData) {
+    let rows = vec![
+        vec!["ID".to_string(), vault.id.clone()],
+        vec!["NAME".to_string(), vault.name.clone()],
+        vec![
+            "DESCRIPTION".to_string(),
+            vault.description.clone().unwrap_or_default(),
+        ],
+        vec![
+            "CONTENT VERSION".to_string(),
+            vault.content_version.to_string(),
+        ],
+        vec!["UPDATED".to_string(), date(vault.updated_at.as_ref())],
+    ];
+
+    print!("{}", table(&[], rows));
+}
+
+pub fn items(items: &[ItemData]) {
+    let rows = items
+        .iter()
+        .map(|item| {
+            vec![
+                item.id.clone(),
+                item.title.clone(),
+                item.category.to_string(),
+                date(item.updated_at.as_ref()),
+            ]
+        })
+        .collect();
+
+    print!("{}", table(&["ID", "TITLE", "CATEGORY", "UPDATED"], rows));
+}
+
+pub fn item(item: &FullItem, reveal: bool) {
+    let details = vec![
+        vec!["ID".to_string(), item.id.clone().unwrap_or_default()],
+        vec!["TITLE".to_string(), item.title.clone()],
+        vec!["CATEGORY".to_string(), item.category.to_string()],
+        vec!["VAULT".to_string(), item.vault.id.clone()],
+    ];
+    let fields = item
+        .fields
+        .iter()
+        .map(|field| {
+            let r#type = field.r#type.clone().unwrap_or_default();
+            let value = match &field.value {
+                Some(_) if field.is_secret() && !reveal => MASK.to_string(),
+                Some(value) => value.clone(),
+                None => String::new(),
+            };
+            let label = field
+                .label
+                .clone()
+                .or_else(|| field.id.clone())
+                .unwrap_or_default();
+
+            vec![label, r#type, value]
+        })
+        .collect();
+
+    print!("{}", table(&[], details));
+    println!();
+    print!("{}", table(&["FIELD", "TYPE", "VALUE"], fields));
+}
+
+fn date(date: Option<&DateTime<Utc>>) -> String {
+    date.map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+/// Render `rows` in aligned columns, below `headers` unless empty
+fn table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut lines: Vec<Vec<String>> = vec![];
+    if !headers.is_empty() {
+        lines.push(headers.iter().map(|header| header.to_string()).collect());
+    }
+    lines.extend(rows);
+
+    let mut widths: Vec<usize> = vec![];
+    for line in &lines {
+        for (column, cell) in line.iter().enumerate() {
+            let width = cell.chars().count();
+            match widths.get_mut(column) {
+                Some(max) => *max = (*max).max(width),
+                None => widths.push(width),
+            }
+        }
+    }
+
+    let mut rendered = String::new();
+    for line in &lines {
+        let mut row = String::new();
+        for (cell, width) in line.iter().zip(&widths) {
+            row.push_str(&format!("{:<width$}  ", cell, width = width));
+        }
+        rendered.push_str(row.trim_end());
+        rendered.push('\n');
+    }
+
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::table;
+
+    #[test]
+    fn aligns_columns() {
+        let rows = vec![
+            vec!["abc".to_string(), "Production".to_string()],
+            vec!["defghi".to_string(), "".to_string()],
+        ];
+
+        assert_eq!(
+            table(&["ID", "NAME"], rows),
+            "ID      NAME\nabc     Production\ndefghi\n"
+        );
+    }
+}
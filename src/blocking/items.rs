@@ -0,0 +1,99 @@
+//! Vault items
+
+use super::Client;
+use crate::{
+    client::HTTPClient,
+    error::Error,
+    filter::Filter,
+    items,
+    models::{
+        item::{FullItem, ItemData},
+        patch::ItemPatch,
+    },
+};
+
+/// Get all items
+pub fn all(
+    client: &Client<impl HTTPClient>,
+    id: &str,
+) -> Result<(Vec<ItemData>, serde_json::Value), Error> {
+    client.block_on(items::all(client.inner(), id))
+}
+
+/// Get all items matching a filter, i.e. `Filter::title_eq("Dell XYZ")`
+pub fn all_filtered(
+    client: &Client<impl HTTPClient>,
+    id: &str,
+    filter: &Filter,
+) -> Result<(Vec<ItemData>, serde_json::Value), Error> {
+    client.block_on(items::all_filtered(client.inner(), id, filter))
+}
+
+/// Get item details, see [`items::get`]
+pub fn get(
+    client: &Client<impl HTTPClient>,
+    vault_id: &str,
+    item_id: &str,
+    inline_files: bool,
+) -> Result<(FullItem, serde_json::Value), Error> {
+    client.block_on(items::get(client.inner(), vault_id, item_id, inline_files))
+}
+
+/// Add an item
+pub fn add(
+    client: &Client<impl HTTPClient>,
+    item: FullItem,
+) -> Result<(ItemData, serde_json::Value), Error> {
+    client.block_on(items::add(client.inner(), item))
+}
+
+/// Replace an item, see [`items::update`]
+pub fn update(
+    client: &Client<impl HTTPClient>,
+    item: FullItem,
+) -> Result<(ItemData, serde_json::Value), Error> {
+    client.block_on(items::update(client.inner(), item))
+}
+
+/// Apply a JSON Patch to an item
+pub fn patch(
+    client: &Client<impl HTTPClient>,
+    vault_id: &str,
+    item_id: &str,
+    patch: &ItemPatch,
+) -> Result<(FullItem, serde_json::Value), Error> {
+    client.block_on(items::patch(client.inner(), vault_id, item_id, patch))
+}
+
+/// Delete an item
+pub fn remove(client: &Client<impl HTTPClient>, id: &str, item_id: &str) -> Result<(), Error> {
+    client.block_on(items::remove(client.inner(), id, item_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        blocking, get_test_client,
+        models::item::{FullItem, ItemBuilder, ItemCategory, LoginItem},
+    };
+
+    #[test]
+    fn add_and_remove_login_item() {
+        let (client, test_vault_id) = get_test_client();
+        let client = blocking::Client::new(client).unwrap();
+
+        let item: FullItem = ItemBuilder::new(&test_vault_id, ItemCategory::Login)
+            .title("Test login item")
+            .username("Bob")
+            .password("")
+            .build()
+            .unwrap();
+
+        let (new_item, _) = blocking::items::add(&client, item).unwrap();
+        let (fetched, _) =
+            blocking::items::get(&client, &test_vault_id, &new_item.id, false).unwrap();
+        assert_eq!(fetched.title, new_item.title);
+
+        blocking::items::remove(&client, &test_vault_id, &new_item.id).unwrap();
+    }
+}
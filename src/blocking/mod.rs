@@ -0,0 +1,77 @@
+//! Blocking API
+//!
+//! Mirrors [`vaults`](crate::vaults) and [`items`](crate::items) for synchronous code, such as
+//! `build.rs` scripts and simple tools, by driving the async API on an internal runtime.
+//!
+//! ```no_run
+//! # #[cfg(feature = "hyper-client")]
+//! use connect_1password::{blocking, error::Error};
+//!
+//! # #[cfg(feature = "hyper-client")]
+//! fn main() -> Result<(), Error> {
+//!     let client = blocking::Client::from_env()?;
+//!
+//!     let (vaults, _) = blocking::vaults::all(&client)?;
+//!     let (items, _) = blocking::items::all(&client, &vaults[0].id)?;
+//!
+//!     Ok(())
+//! }
+//! # #[cfg(not(feature = "hyper-client"))]
+//! # fn main() {}
+//! ```
+
+use crate::{client::HTTPClient, error::Error};
+use std::future::Future;
+use tokio::runtime::{Builder, Runtime};
+
+pub mod items;
+pub mod vaults;
+
+/// Represents a blocking client, wrapping any [`HTTPClient`] together with its own runtime.
+///
+/// # Panics
+///
+/// The blocking functions panic when called from within an async runtime, use the async API
+/// there instead.
+#[derive(Debug)]
+pub struct Client<C> {
+    inner: C,
+    runtime: Runtime,
+}
+
+impl<C: HTTPClient> Client<C> {
+    /// Create a new instance sending requests with `inner`
+    pub fn new(inner: C) -> Result<Self, Error> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(Error::new_config_error)?;
+
+        Ok(Self { inner, runtime })
+    }
+
+    /// Returns the wrapped async client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Run `future` to completion on the internal runtime.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+}
+
+#[cfg(feature = "hyper-client")]
+impl Client<crate::client::Client> {
+    /// Create an instance by fetching defaults from the host ENV.
+    ///
+    /// # Fields
+    ///
+    /// - `OP_API_TOKEN`: provide the 1Password Connect API token.
+    /// - `OP_SERVER_URL`: provide full URL to the host server, i.e. `http://localhost:8080`
+    pub fn from_env() -> Result<Self, Error> {
+        let inner = crate::client::ClientBuilder::from_env()?.build()?;
+
+        Self::new(inner)
+    }
+}
@@ -0,0 +1,40 @@
+//! Vaults
+
+use super::Client;
+use crate::{client::HTTPClient, error::Error, filter::Filter, models::VaultData, vaults};
+
+/// Get all known vaults
+pub fn all(client: &Client<impl HTTPClient>) -> Result<(Vec<VaultData>, serde_json::Value), Error> {
+    client.block_on(vaults::all(client.inner()))
+}
+
+/// Get all vaults matching a filter, i.e. `Filter::name_eq("vault")`
+pub fn all_filtered(
+    client: &Client<impl HTTPClient>,
+    filter: &Filter,
+) -> Result<(Vec<VaultData>, serde_json::Value), Error> {
+    client.block_on(vaults::all_filtered(client.inner(), filter))
+}
+
+/// Get vault details
+pub fn get(
+    client: &Client<impl HTTPClient>,
+    id: &str,
+) -> Result<(VaultData, serde_json::Value), Error> {
+    client.block_on(vaults::get(client.inner(), id))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{blocking, get_test_client};
+
+    #[test]
+    fn get() {
+        let (client, test_vault_id) = get_test_client();
+        let client = blocking::Client::new(client).unwrap();
+
+        let (vault, _) = blocking::vaults::get(&client, &test_vault_id).unwrap();
+
+        assert_eq!(vault.id, test_vault_id);
+    }
+}
@@ -0,0 +1,327 @@
+//! Client builder
+
+use super::{
+    proxy::{Proxy, ProxyConnector},
+    retry::{
+        ExponentialBackoff, RetryPolicy, DEFAULT_MAX_ATTEMPTS, DEFAULT_MAX_BACKOFF,
+        DEFAULT_MIN_BACKOFF,
+    },
+    tls::TlsOptions,
+    Client,
+};
+use crate::error::Error;
+use hyper::{
+    client::connect::HttpConnector,
+    header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT},
+};
+use std::{sync::Arc, time::Duration};
+
+/// Describes which HTTP versions the client may use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HttpVersion {
+    /// Negotiate HTTP/2 via ALPN, falling back to HTTP/1.1
+    #[default]
+    Negotiate,
+    /// Only use HTTP/1.1
+    Http1Only,
+    /// Only use HTTP/2
+    Http2Only,
+}
+
+/// This is a ClientBuilder, used to configure a [`Client`].
+///
+/// ```
+/// use connect_1password::client::{ClientBuilder, HttpVersion};
+/// use std::time::Duration;
+///
+/// let client = ClientBuilder::new("token", "http://localhost:8080")
+///     .timeout(Duration::from_secs(10))
+///     .connect_timeout(Duration::from_secs(2))
+///     .retry_attempts(3)
+///     .user_agent("my-service/1.0")
+///     .default_header("X-Request-Source", "deploy")
+///     .http_version(HttpVersion::Http1Only)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct ClientBuilder {
+    token: String,
+    server_url: String,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    retry_attempts: u32,
+    min_backoff: Duration,
+    max_backoff: Duration,
+    retry_policy: Option<Arc<dyn RetryPolicy>>,
+    user_agent: String,
+    default_headers: Vec<(String, String)>,
+    http_version: HttpVersion,
+    tls: TlsOptions,
+    proxy: Option<Proxy>,
+    proxy_from_env: bool,
+}
+
+impl std::fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientBuilder")
+            .field("token", &super::REDACTED)
+            .field("server_url", &self.server_url)
+            .field("timeout", &self.timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("retry_attempts", &self.retry_attempts)
+            .field("min_backoff", &self.min_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("retry_policy", &self.retry_policy)
+            .field("user_agent", &self.user_agent)
+            .field("default_headers", &self.default_headers)
+            .field("http_version", &self.http_version)
+            .field("tls", &self.tls)
+            .field("proxy", &self.proxy)
+            .field("proxy_from_env", &self.proxy_from_env)
+            .finish()
+    }
+}
+
+impl ClientBuilder {
+    /// Create a new instance
+    ///
+    /// # Fields
+    ///
+    /// - `token`: provide the 1Password Connect API token.
+    /// - `server_url`: provide full URL to the host server, i.e. `http://localhost:8080`
+    pub fn new(token: &str, server_url: &str) -> Self {
+        Self {
+            token: token.to_string(),
+            server_url: server_url.trim_end_matches('/').to_string(),
+            timeout: None,
+            connect_timeout: None,
+            retry_attempts: DEFAULT_MAX_ATTEMPTS,
+            min_backoff: DEFAULT_MIN_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            retry_policy: None,
+            user_agent: format!("connect-1password/{}", env!("CARGO_PKG_VERSION")),
+            default_headers: vec![],
+            http_version: HttpVersion::default(),
+            tls: TlsOptions::default(),
+            proxy: None,
+            proxy_from_env: false,
+        }
+    }
+
+    /// Create an instance by fetching defaults from the host ENV.
+    ///
+    /// # Fields
+    ///
+    /// - `OP_API_TOKEN`: provide the 1Password Connect API token.
+    /// - `OP_SERVER_URL`: provide full URL to the host server, i.e. `http://localhost:8080`
+    pub fn from_env() -> Result<Self, Error> {
+        let (token, host) = super::env_config()?;
+
+        Ok(Self::new(&token, &host))
+    }
+
+    /// Abort a request, including reading its response headers, if it takes longer than `timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Abort establishing a connection if it takes longer than `timeout`.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Specify how often a failed request is attempted by the default [`ExponentialBackoff`]
+    /// policy, defaults to 5.
+    pub fn retry_attempts(mut self, attempts: u32) -> Self {
+        self.retry_attempts = attempts;
+        self
+    }
+
+    /// Specify the bounds of the exponential backoff between attempts, defaults to 100ms and 20s.
+    pub fn backoff(mut self, min: Duration, max: Duration) -> Self {
+        self.min_backoff = min;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Decide when failed requests are attempted again with a custom [`RetryPolicy`], replacing
+    /// the settings of [`ClientBuilder::retry_attempts`] and [`ClientBuilder::backoff`].
+    pub fn retry_policy(mut self, policy: impl RetryPolicy + 'static) -> Self {
+        self.retry_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Specify the `User-Agent` header sent with every request.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Add a header sent with every request, this is validated by [`ClientBuilder::build`].
+    pub fn default_header(mut self, name: &str, value: &str) -> Self {
+        self.default_headers
+            .push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Specify which HTTP versions may be used.
+    pub fn http_version(mut self, version: HttpVersion) -> Self {
+        self.http_version = version;
+        self
+    }
+
+    /// Trust the PEM encoded CA certificates in `pem`, in addition to the built-in roots.
+    pub fn add_root_certificate_pem(mut self, pem: &[u8]) -> Self {
+        self.tls.root_certificates_pem.push(pem.to_vec());
+        self
+    }
+
+    /// Specify whether the root certificates of the host are trusted, defaults to `true`.
+    ///
+    /// Disable this to only trust certificates added with
+    /// [`ClientBuilder::add_root_certificate_pem`].
+    pub fn tls_built_in_root_certs(mut self, enabled: bool) -> Self {
+        self.tls.built_in_roots = enabled;
+        self
+    }
+
+    /// Present a client certificate for mutual TLS.
+    ///
+    /// # Fields
+    ///
+    /// - `cert_chain_pem`: PEM encoded certificate, optionally followed by its intermediates.
+    /// - `key_pem`: PEM encoded PKCS#8, PKCS#1 or SEC1 private key.
+    pub fn identity_pem(mut self, cert_chain_pem: &[u8], key_pem: &[u8]) -> Self {
+        self.tls.identity_pem = Some((cert_chain_pem.to_vec(), key_pem.to_vec()));
+        self
+    }
+
+    /// Pin the Base64 encoded SHA-256 hash of a SubjectPublicKeyInfo, i.e.
+    /// `sha256/19rvmT7myEEH7952JlM8S1EELpducj9jN+SfoHzFr5Y=`.
+    ///
+    /// Once a pin is added, connections are only allowed if a certificate in the server chain
+    /// matches one of the pins.
+    pub fn pin_spki_sha256(mut self, pin: &str) -> Self {
+        self.tls.spki_pins.push(pin.to_string());
+        self
+    }
+
+    /// Route requests through `proxy`.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Route requests through the proxy configured in the host ENV, unless a proxy was given
+    /// explicitly. See [`Proxy::from_env`] for the variables used.
+    pub fn proxy_from_env(mut self) -> Self {
+        self.proxy_from_env = true;
+        self
+    }
+
+    /// Execute the builder
+    pub fn build(self) -> Result<Client, Error> {
+        let mut default_headers = HeaderMap::new();
+        default_headers.insert(
+            USER_AGENT,
+            HeaderValue::from_str(&self.user_agent).map_err(Error::new_config_error)?,
+        );
+        for (name, value) in &self.default_headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(Error::new_config_error)?;
+            let value = HeaderValue::from_str(value).map_err(Error::new_config_error)?;
+            default_headers.append(name, value);
+        }
+
+        let mut http = HttpConnector::new();
+        http.enforce_http(false);
+        http.set_connect_timeout(self.connect_timeout);
+
+        let builder = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_tls_config(self.tls.client_config()?)
+            .https_or_http();
+        let proxy = match self.proxy {
+            Some(proxy) => Some(proxy),
+            None if self.proxy_from_env => Proxy::from_env(&self.server_url)?,
+            None => None,
+        }
+        .map(Arc::new);
+        let connector = ProxyConnector::new(http, proxy.clone());
+
+        let https = match self.http_version {
+            HttpVersion::Negotiate => builder
+                .enable_http1()
+                .enable_http2()
+                .wrap_connector(connector),
+            HttpVersion::Http1Only => builder.enable_http1().wrap_connector(connector),
+            HttpVersion::Http2Only => builder.enable_http2().wrap_connector(connector),
+        };
+
+        let https_client = hyper::Client::builder()
+            .http2_only(self.http_version == HttpVersion::Http2Only)
+            .build::<_, hyper::Body>(https);
+
+        let retry_policy = self.retry_policy.unwrap_or_else(|| {
+            Arc::new(
+                ExponentialBackoff::new(self.retry_attempts)
+                    .backoff(self.min_backoff, self.max_backoff),
+            )
+        });
+
+        Ok(Client {
+            api_key: self.token,
+            server_url: self.server_url,
+            https_client,
+            timeout: self.timeout,
+            retry_policy,
+            default_headers,
+            proxy,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClientBuilder, HttpVersion, Proxy};
+    use std::time::Duration;
+
+    #[test]
+    fn build_with_options() {
+        let client = ClientBuilder::new("token", "http://localhost:8080/")
+            .timeout(Duration::from_secs(1))
+            .retry_attempts(1)
+            .http_version(HttpVersion::Http2Only)
+            .build()
+            .unwrap();
+
+        assert_eq!(client.server_url, "http://localhost:8080");
+        assert!(format!("{:?}", client.retry_policy).contains("max_attempts: 1"));
+        assert_eq!(client.timeout, Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn build_rejects_invalid_header() {
+        let result = ClientBuilder::new("token", "http://localhost:8080")
+            .default_header("Invalid Header", "value")
+            .build();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn debug_redacts_secrets() {
+        let proxy = Proxy::new("http://proxy.local:3128")
+            .unwrap()
+            .basic_auth("user", "proxy-secret");
+        let builder = ClientBuilder::new("api-secret", "http://localhost:8080").proxy(proxy);
+        let client = builder.clone().build().unwrap();
+
+        for debug in [format!("{:?}", builder), format!("{:?}", client)] {
+            assert!(!debug.contains("api-secret"));
+            assert!(!debug.contains("proxy-secret"));
+            assert!(debug.contains("\"user\""));
+        }
+    }
+}
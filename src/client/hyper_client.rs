@@ -0,0 +1,147 @@
+//! Hyper HTTP client
+
+use super::{
+    proxy::ProxyConnector, retry, ClientBuilder, ConnectRequest, ConnectResponse, HTTPClient,
+    Outcome, Proxy, RetryPolicy,
+};
+use crate::error::Error;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use hyper::{
+    header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, PROXY_AUTHORIZATION},
+    Body, Client as HyperClient, Request, Uri,
+};
+use hyper_rustls::HttpsConnector;
+use std::{sync::Arc, time::Duration};
+
+/// Represents a (Hyper) HTTP client.
+///
+/// Use [`ClientBuilder`] to configure timeouts, retries and headers.
+pub struct Client {
+    pub(super) api_key: String,
+    pub(super) server_url: String,
+    pub(super) https_client: HyperClient<HttpsConnector<ProxyConnector>>,
+    pub(super) timeout: Option<Duration>,
+    pub(super) retry_policy: Arc<dyn RetryPolicy>,
+    pub(super) default_headers: HeaderMap,
+    pub(super) proxy: Option<Arc<Proxy>>,
+}
+
+impl std::fmt::Debug for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Client")
+            .field("api_key", &super::REDACTED)
+            .field("server_url", &self.server_url)
+            .field("https_client", &self.https_client)
+            .field("timeout", &self.timeout)
+            .field("retry_policy", &self.retry_policy)
+            .field("default_headers", &self.default_headers)
+            .field("proxy", &self.proxy)
+            .finish()
+    }
+}
+
+/// Sends requests, attempting them again for as long as the client's [`RetryPolicy`] allows.
+///
+/// If the last attempt received a response, it is returned regardless of its status.
+#[async_trait]
+impl HTTPClient for Client {
+    async fn execute(&self, request: ConnectRequest) -> Result<ConnectResponse, Error> {
+        request.check_path()?;
+        let url = format!("{}/{}", self.server_url, request.path_and_query());
+        let uri: Uri = url.parse().map_err(Error::new_config_error)?;
+
+        let mut headers = self.default_headers.clone();
+        headers.extend(request.headers().clone());
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", self.api_key))?,
+        );
+        if let Some(value) = self
+            .proxy
+            .as_ref()
+            .and_then(|proxy| proxy.http_authorization(&uri))
+        {
+            headers.insert(PROXY_AUTHORIZATION, value);
+        }
+
+        let send = || {
+            let body = match request.body_bytes() {
+                Some(value) => Body::from(value.clone()),
+                None => Body::empty(),
+            };
+            let mut req = Request::new(body);
+            *req.method_mut() = request.method().into();
+            *req.uri_mut() = uri.clone();
+            *req.headers_mut() = headers.clone();
+
+            async move {
+                let response = self
+                    .https_client
+                    .request(req)
+                    .await
+                    .map_err(|err| (transport_outcome(&err), err.to_string()))?;
+                let (parts, body) = response.into_parts();
+
+                Ok(ConnectResponse {
+                    status: parts.status,
+                    headers: parts.headers,
+                    body: Box::pin(body.map_err(Error::new_network_error)),
+                })
+            }
+        };
+
+        retry::send_with_retries(self.retry_policy.as_ref(), &request, self.timeout, send).await
+    }
+}
+
+fn transport_outcome(err: &hyper::Error) -> Outcome {
+    if err.is_connect() {
+        Outcome::Connect
+    } else {
+        Outcome::Transport
+    }
+}
+
+/// Create an instance by fetching defaults from the host ENV.
+///
+/// # Fields
+///
+/// - `OP_API_TOKEN`: provide the 1Password Connect API token.
+/// - `OP_SERVER_URL`: provide full URL to the host server, i.e. `http://localhost:8080`
+///
+/// # Panics
+///
+/// Panics if either variable is missing, use [`ClientBuilder::from_env`] to handle this instead.
+impl Default for Client {
+    fn default() -> Self {
+        ClientBuilder::from_env()
+            .and_then(ClientBuilder::build)
+            .expect("1Password API token and Connect server URL expected!")
+    }
+}
+
+impl Client {
+    /// Create a new instance
+    ///
+    /// # Fields
+    ///
+    /// - `token`: provide the 1Password Connect API token.
+    /// - `server_url`: provide full URL to the host server, i.e. `http://localhost:8080`
+    pub fn new(token: &str, server_url: &str) -> Self {
+        ClientBuilder::new(token, server_url)
+            .build()
+            .expect("default client configuration is valid")
+    }
+
+    /// Create a builder to configure a new instance
+    pub fn builder(token: &str, server_url: &str) -> ClientBuilder {
+        ClientBuilder::new(token, server_url)
+    }
+
+    /// Returns the 1Password Connect API token.
+    pub fn token(&self) -> String {
+        self.api_key.clone()
+    }
+}
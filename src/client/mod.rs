@@ -0,0 +1,113 @@
+//! HTTP Client
+
+use crate::error::{CustomError, Error, RequestNotSuccessful};
+use async_trait::async_trait;
+use bytes::Bytes;
+use dotenvy::dotenv;
+use futures::Stream;
+use log::debug;
+use serde_json::Value;
+use std::pin::Pin;
+
+/// Shown instead of tokens, passwords and private keys in `Debug` output
+#[cfg(any(feature = "hyper-client", feature = "reqwest"))]
+const REDACTED: &str = "[REDACTED]";
+
+/// A stream of bytes, as returned when downloading raw content such as file attachments.
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, Error>> + Send>>;
+
+#[cfg(feature = "hyper-client")]
+mod builder;
+#[cfg(feature = "hyper-client")]
+mod hyper_client;
+#[cfg(feature = "hyper-client")]
+mod proxy;
+mod request;
+#[cfg(feature = "reqwest")]
+mod reqwest_client;
+mod retry;
+#[cfg(feature = "hyper-client")]
+mod tls;
+
+#[cfg(feature = "hyper-client")]
+pub use builder::{ClientBuilder, HttpVersion};
+#[cfg(feature = "hyper-client")]
+pub use hyper_client::Client;
+#[cfg(feature = "hyper-client")]
+pub use proxy::{Proxy, ProxyScheme};
+pub use request::{ConnectRequest, ConnectResponse, Method};
+#[cfg(feature = "reqwest")]
+pub use reqwest_client::ReqwestClient;
+pub use retry::{Attempt, ExponentialBackoff, NoRetry, Outcome, RetryPolicy};
+
+/// Interface for any compatible HTTP client
+///
+/// Transports only implement [`HTTPClient::execute`], decoding responses is provided.
+#[async_trait]
+pub trait HTTPClient: Send + Sync {
+    /// Send a request using the underlying HTTP client, returning the response regardless of
+    /// its status.
+    async fn execute(&self, request: ConnectRequest) -> Result<ConnectResponse, Error>;
+
+    /// Send a request, decoding the JSON response body.
+    async fn send_request<T>(&self, request: ConnectRequest) -> Result<(T, Value), Error>
+    where
+        T: serde::de::DeserializeOwned + std::fmt::Debug,
+    {
+        let mut bytes = send_checked(self, request).await?.bytes().await?;
+
+        if bytes.is_empty() {
+            bytes = Bytes::from_static(b"{}");
+        }
+        let decoded = serde_json::from_slice(&bytes).map_err(Error::new_parsing_error)?;
+        let raw_json: Value = serde_json::from_slice(&bytes)?;
+
+        Ok((decoded, raw_json))
+    }
+
+    /// Send a request, returning the raw response body as a stream instead of decoding it as
+    /// JSON.
+    async fn send_request_stream(&self, request: ConnectRequest) -> Result<ByteStream, Error> {
+        Ok(send_checked(self, request).await?.body)
+    }
+}
+
+/// Send a request, decoding the error unless the server responded with a successful status.
+///
+/// The path is checked here rather than only in the transports, so that test clients reject
+/// the same requests.
+pub(crate) async fn send_checked<C: HTTPClient + ?Sized>(
+    client: &C,
+    request: ConnectRequest,
+) -> Result<ConnectResponse, Error> {
+    request.check_path()?;
+    let path = request.segments().to_vec();
+    let response = client.execute(request).await?;
+    let status = response.status;
+
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let bytes = response.bytes().await?;
+    let body = String::from_utf8_lossy(&bytes).to_string();
+    debug!("Client error! Status: {}, Body: {}", status, &body);
+
+    Err(Error::new_response_error(
+        RequestNotSuccessful::new(status, body),
+        &path,
+    ))
+}
+
+/// Read the API token and server URL from `OP_API_TOKEN` and `OP_SERVER_URL`.
+#[allow(dead_code)]
+pub(crate) fn env_config() -> Result<(String, String), Error> {
+    dotenv().ok();
+
+    let env_var = |key: &str| {
+        std::env::var(key)
+            .map_err(|_| Error::new_config_error(CustomError::new(&format!("{} is not set", key))))
+    };
+
+    Ok((env_var("OP_API_TOKEN")?, env_var("OP_SERVER_URL")?))
+}
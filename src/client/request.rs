@@ -0,0 +1,340 @@
+//! Requests and responses
+
+use super::ByteStream;
+use crate::error::{CustomError, Error};
+use bytes::Bytes;
+use futures::TryStreamExt;
+use http::{
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE},
+    StatusCode,
+};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::Serialize;
+use std::fmt;
+
+/// Characters escaped in path segments and query parameters, everything but the RFC 3986
+/// unreserved set.
+const ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Describes the HTTP methods used by the Connect API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    /// `GET`
+    Get,
+    /// `POST`
+    Post,
+    /// `PUT`
+    Put,
+    /// `PATCH`
+    Patch,
+    /// `DELETE`
+    Delete,
+}
+
+impl Method {
+    /// Returns the method as it is sent on the wire, i.e. `"GET"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Patch => "PATCH",
+            Method::Delete => "DELETE",
+        }
+    }
+
+    /// Returns true if sending a request with this method more than once has the same effect as
+    /// sending it once.
+    pub fn is_idempotent(&self) -> bool {
+        matches!(self, Method::Get | Method::Put | Method::Delete)
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<Method> for http::Method {
+    fn from(method: Method) -> Self {
+        match method {
+            Method::Get => http::Method::GET,
+            Method::Post => http::Method::POST,
+            Method::Put => http::Method::PUT,
+            Method::Patch => http::Method::PATCH,
+            Method::Delete => http::Method::DELETE,
+        }
+    }
+}
+
+/// This is a request to the Connect API, independent of the transport sending it.
+///
+/// Path segments and query parameters are kept unencoded and only percent-encoded when the
+/// request is sent, so IDs containing `/`, `?` or `&` cannot change the request.
+///
+/// ```
+/// use connect_1password::client::{ConnectRequest, Method};
+///
+/// let request = ConnectRequest::get(&["v1", "vaults", "a/b", "items"])
+///     .query("filter", "title eq \"Dell & Co\"");
+///
+/// assert_eq!(request.method(), Method::Get);
+/// assert_eq!(
+///     request.path_and_query(),
+///     "v1/vaults/a%2Fb/items?filter=title%20eq%20%22Dell%20%26%20Co%22"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectRequest {
+    method: Method,
+    segments: Vec<String>,
+    query: Vec<(String, String)>,
+    headers: HeaderMap,
+    body: Option<Bytes>,
+}
+
+impl ConnectRequest {
+    /// Create a new request for the path made up of `segments`
+    pub fn new(method: Method, segments: &[&str]) -> Self {
+        Self {
+            method,
+            segments: segments.iter().map(|segment| segment.to_string()).collect(),
+            query: vec![],
+            headers: HeaderMap::new(),
+            body: None,
+        }
+    }
+
+    /// Create a new `GET` request
+    pub fn get(segments: &[&str]) -> Self {
+        Self::new(Method::Get, segments)
+    }
+
+    /// Create a new `POST` request
+    pub fn post(segments: &[&str]) -> Self {
+        Self::new(Method::Post, segments)
+    }
+
+    /// Create a new `PUT` request
+    pub fn put(segments: &[&str]) -> Self {
+        Self::new(Method::Put, segments)
+    }
+
+    /// Create a new `PATCH` request
+    pub fn patch(segments: &[&str]) -> Self {
+        Self::new(Method::Patch, segments)
+    }
+
+    /// Create a new `DELETE` request
+    pub fn delete(segments: &[&str]) -> Self {
+        Self::new(Method::Delete, segments)
+    }
+
+    /// Add a query parameter.
+    pub fn query(mut self, key: &str, value: &str) -> Self {
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Add a header.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Set the body.
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Set the body to `value` serialized as JSON.
+    pub fn json<T: Serialize + ?Sized>(self, value: &T) -> Result<Self, Error> {
+        let body = serde_json::to_vec(value)?;
+
+        Ok(self
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+            .body(body))
+    }
+
+    /// Returns the method.
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    /// Returns the unencoded path segments.
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+
+    /// Returns the unencoded query parameters.
+    pub fn query_pairs(&self) -> &[(String, String)] {
+        &self.query
+    }
+
+    /// Returns the headers.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Returns the body.
+    pub fn body_bytes(&self) -> Option<&Bytes> {
+        self.body.as_ref()
+    }
+
+    /// Returns the percent-encoded path, relative to the server URL.
+    pub fn path(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| utf8_percent_encode(segment, ENCODE_SET).to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Fails if a path segment is empty, `.` or `..`. URL parsers resolve dot segments against
+    /// the preceding segments even when percent-encoded, and an empty one, i.e. a missing ID,
+    /// would address a different endpoint.
+    pub fn check_path(&self) -> Result<(), Error> {
+        match self
+            .segments
+            .iter()
+            .find(|segment| matches!(segment.as_str(), "" | "." | ".."))
+        {
+            Some(segment) => Err(CustomError::new(&format!(
+                "invalid path segment {:?} in {}",
+                segment,
+                self.path()
+            ))
+            .into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the percent-encoded path followed by the query, if any.
+    pub fn path_and_query(&self) -> String {
+        if self.query.is_empty() {
+            return self.path();
+        }
+
+        let query = self
+            .query
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "{}={}",
+                    utf8_percent_encode(key, ENCODE_SET),
+                    utf8_percent_encode(value, ENCODE_SET)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+
+        format!("{}?{}", self.path(), query)
+    }
+}
+
+/// This is a response from the Connect API, as returned by [`HTTPClient::execute`].
+///
+/// [`HTTPClient::execute`]: super::HTTPClient::execute
+pub struct ConnectResponse {
+    /// The status of the response.
+    pub status: StatusCode,
+    /// The headers of the response.
+    pub headers: HeaderMap,
+    /// The body of the response.
+    pub body: ByteStream,
+}
+
+impl fmt::Debug for ConnectResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectResponse")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConnectResponse {
+    /// Create a response with a body which is already in memory
+    pub fn new(status: StatusCode, body: impl Into<Bytes>) -> Self {
+        let body: Bytes = body.into();
+
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: Box::pin(futures::stream::once(async move { Ok(body) })),
+        }
+    }
+
+    /// Collect the whole body.
+    pub async fn bytes(self) -> Result<Bytes, Error> {
+        let bytes = self
+            .body
+            .try_fold(Vec::new(), |mut acc, chunk| async move {
+                acc.extend_from_slice(&chunk);
+                Ok(acc)
+            })
+            .await?;
+
+        Ok(Bytes::from(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConnectRequest, ConnectResponse, Method};
+    use http::StatusCode;
+
+    #[test]
+    fn encodes_path_segments() {
+        let request = ConnectRequest::delete(&["v1", "vaults", "../admin", "items", "a?b&c=d"]);
+
+        assert_eq!(request.method(), Method::Delete);
+        assert_eq!(request.path(), "v1/vaults/..%2Fadmin/items/a%3Fb%26c%3Dd");
+        assert_eq!(request.path_and_query(), request.path());
+        assert!(request.check_path().is_ok());
+    }
+
+    #[test]
+    fn rejects_dot_segments() {
+        for id in ["", ".", ".."] {
+            let request = ConnectRequest::get(&["v1", "vaults", id, "items"]);
+
+            assert!(request.check_path().is_err());
+        }
+
+        let request = ConnectRequest::get(&["v1", "vaults", "...", "items", ".a"]);
+        assert!(request.check_path().is_ok());
+    }
+
+    #[test]
+    fn encodes_query() {
+        let request = ConnectRequest::get(&["v1", "activity"])
+            .query("limit", "10")
+            .query("a+b", "c d");
+
+        assert_eq!(request.path_and_query(), "v1/activity?limit=10&a%2Bb=c%20d");
+    }
+
+    #[test]
+    fn serializes_json_body() {
+        let request = ConnectRequest::post(&["v1"])
+            .json(&serde_json::json!({"title": "x"}))
+            .unwrap();
+
+        assert_eq!(request.body_bytes().unwrap().as_ref(), br#"{"title":"x"}"#);
+        assert_eq!(request.headers()["content-type"], "application/json");
+    }
+
+    #[tokio::test]
+    async fn collects_response_body() {
+        let response = ConnectResponse::new(StatusCode::OK, "{}");
+
+        assert_eq!(response.bytes().await.unwrap().as_ref(), b"{}");
+    }
+}
@@ -0,0 +1,184 @@
+//! Reqwest HTTP client
+
+use super::{
+    env_config, retry, ConnectRequest, ConnectResponse, ExponentialBackoff, HTTPClient, Outcome,
+    RetryPolicy,
+};
+use crate::error::Error;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use http::header::{HeaderValue, ACCEPT};
+use std::{sync::Arc, time::Duration};
+
+/// Represents a (Reqwest) HTTP client, available with the `reqwest` feature.
+///
+/// Proxies, TLS and connection pooling are configured on the wrapped [`reqwest::Client`], pick a
+/// TLS backend with the `reqwest-rustls` or `reqwest-native-tls` feature.
+///
+/// ```
+/// use connect_1password::client::{ExponentialBackoff, ReqwestClient};
+/// use std::time::Duration;
+///
+/// let client = ReqwestClient::new("token", "http://localhost:8080")
+///     .timeout(Duration::from_secs(10))
+///     .retry_policy(ExponentialBackoff::new(3));
+/// ```
+#[derive(Clone)]
+pub struct ReqwestClient {
+    client: reqwest::Client,
+    api_key: String,
+    server_url: String,
+    timeout: Option<Duration>,
+    retry_policy: Arc<dyn RetryPolicy>,
+}
+
+impl std::fmt::Debug for ReqwestClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReqwestClient")
+            .field("client", &self.client)
+            .field("api_key", &super::REDACTED)
+            .field("server_url", &self.server_url)
+            .field("timeout", &self.timeout)
+            .field("retry_policy", &self.retry_policy)
+            .finish()
+    }
+}
+
+impl ReqwestClient {
+    /// Create a new instance
+    ///
+    /// # Fields
+    ///
+    /// - `token`: provide the 1Password Connect API token.
+    /// - `server_url`: provide full URL to the host server, i.e. `http://localhost:8080`
+    pub fn new(token: &str, server_url: &str) -> Self {
+        Self::with_client(reqwest::Client::new(), token, server_url)
+    }
+
+    /// Create a new instance sending requests with an existing `client`
+    pub fn with_client(client: reqwest::Client, token: &str, server_url: &str) -> Self {
+        Self {
+            client,
+            api_key: token.to_string(),
+            server_url: server_url.trim_end_matches('/').to_string(),
+            timeout: None,
+            retry_policy: Arc::new(ExponentialBackoff::default()),
+        }
+    }
+
+    /// Create an instance by fetching defaults from the host ENV.
+    ///
+    /// # Fields
+    ///
+    /// - `OP_API_TOKEN`: provide the 1Password Connect API token.
+    /// - `OP_SERVER_URL`: provide full URL to the host server, i.e. `http://localhost:8080`
+    pub fn from_env() -> Result<Self, Error> {
+        let (token, host) = env_config()?;
+
+        Ok(Self::new(&token, &host))
+    }
+
+    /// Abort a request, including reading its response headers, if it takes longer than `timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Decide when failed requests are attempted again, defaults to [`ExponentialBackoff`].
+    pub fn retry_policy(mut self, policy: impl RetryPolicy + 'static) -> Self {
+        self.retry_policy = Arc::new(policy);
+        self
+    }
+
+    /// Returns the 1Password Connect API token.
+    pub fn token(&self) -> String {
+        self.api_key.clone()
+    }
+}
+
+#[async_trait]
+impl HTTPClient for ReqwestClient {
+    async fn execute(&self, request: ConnectRequest) -> Result<ConnectResponse, Error> {
+        request.check_path()?;
+        let url = format!("{}/{}", self.server_url, request.path_and_query());
+
+        let send = || {
+            let mut builder = self
+                .client
+                .request(request.method().into(), &url)
+                .headers(request.headers().clone())
+                .header(ACCEPT, HeaderValue::from_static("application/json"))
+                .bearer_auth(&self.api_key);
+            if let Some(body) = request.body_bytes() {
+                builder = builder.body(body.clone());
+            }
+
+            async move {
+                let response = builder
+                    .send()
+                    .await
+                    .map_err(|err| (transport_outcome(&err), err.to_string()))?;
+
+                Ok(ConnectResponse {
+                    status: response.status(),
+                    headers: response.headers().clone(),
+                    body: Box::pin(response.bytes_stream().map_err(Error::new_network_error)),
+                })
+            }
+        };
+
+        retry::send_with_retries(self.retry_policy.as_ref(), &request, self.timeout, send).await
+    }
+}
+
+fn transport_outcome(err: &reqwest::Error) -> Outcome {
+    if err.is_connect() {
+        Outcome::Connect
+    } else if err.is_timeout() {
+        Outcome::Timeout
+    } else {
+        Outcome::Transport
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReqwestClient;
+    use crate::client::{ConnectRequest, HTTPClient};
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Body, Request, Response, Server,
+    };
+    use std::convert::Infallible;
+
+    #[tokio::test]
+    async fn sends_requests() {
+        // Echo the request line and authorization header back as JSON
+        let make_service = make_service_fn(|_| async {
+            Ok::<_, Infallible>(service_fn(|req: Request<Body>| async move {
+                let json = serde_json::json!({
+                    "method": req.method().as_str(),
+                    "uri": req.uri().to_string(),
+                    "authorization": req.headers()["authorization"].to_str().unwrap(),
+                });
+
+                Ok::<_, Infallible>(Response::new(Body::from(json.to_string())))
+            }))
+        });
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let client = ReqwestClient::new("token", &format!("http://{}/", addr));
+        let (_, json) = client
+            .send_request::<serde_json::Value>(
+                ConnectRequest::delete(&["v1", "vaults", "a/b", "items"]).query("x", "1&2"),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(json["method"], "DELETE");
+        assert_eq!(json["uri"], "/v1/vaults/a%2Fb/items?x=1%262");
+        assert_eq!(json["authorization"], "Bearer token");
+    }
+}
@@ -0,0 +1,524 @@
+//! Retry policies
+
+use super::Method;
+use http::StatusCode;
+use log::debug;
+use std::{fmt, time::Duration};
+use uuid::Uuid;
+// Only the transports send requests
+#[cfg(any(feature = "hyper-client", feature = "reqwest"))]
+use {
+    super::{ConnectRequest, ConnectResponse},
+    crate::error::{CustomError, Error},
+    http::{header::RETRY_AFTER, HeaderMap},
+    std::{future::Future, time::Instant},
+};
+
+pub(super) const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+pub(super) const DEFAULT_MIN_BACKOFF: Duration = Duration::from_millis(100);
+pub(super) const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(20);
+
+/// Describes how a single attempt at sending a request ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The server responded with this status
+    Response(StatusCode),
+    /// No connection could be established, so the request was never sent
+    Connect,
+    /// The attempt took longer than the configured timeout
+    Timeout,
+    /// The connection failed after it was established, the request may have been processed
+    Transport,
+}
+
+impl Outcome {
+    /// Returns true if the server responded with a success status.
+    pub fn is_success(&self) -> bool {
+        matches!(self, Outcome::Response(status) if status.is_success())
+    }
+}
+
+/// Defines a single attempt at sending a request, passed to a [`RetryPolicy`]
+#[derive(Debug, Clone)]
+pub struct Attempt<'a> {
+    /// The method of the request.
+    pub method: Method,
+    /// The encoded path and query of the request, relative to the server URL.
+    pub path: &'a str,
+    /// The number of this attempt, starting at 1.
+    pub number: u32,
+    /// How the attempt ended.
+    pub outcome: Outcome,
+    /// The delay requested by the server with a `Retry-After` header.
+    pub retry_after: Option<Duration>,
+    /// How long the attempt took.
+    pub elapsed: Duration,
+}
+
+/// Decides whether, and after which delay, a request is attempted again.
+///
+/// The [`Client`](super::Client) asks the policy after every attempt that did not succeed, and
+/// reports every attempt to [`RetryPolicy::on_attempt`], which can be used for telemetry.
+pub trait RetryPolicy: fmt::Debug + Send + Sync {
+    /// Returns the delay before the next attempt, or `None` to stop retrying.
+    fn retry(&self, attempt: &Attempt<'_>) -> Option<Duration>;
+
+    /// Called after every attempt with the delay before the next one, if any.
+    fn on_attempt(&self, attempt: &Attempt<'_>, delay: Option<Duration>) {
+        debug!(
+            "{} {} attempt {} ended with {:?} after {:?}, retrying after {:?}",
+            attempt.method, attempt.path, attempt.number, attempt.outcome, attempt.elapsed, delay
+        );
+    }
+}
+
+/// This is the default [`RetryPolicy`], retrying with an exponentially growing, jittered delay.
+///
+/// Requests are retried when the server responds with `429`, `502`, `503` or `504`, on timeouts
+/// and when the connection fails. Requests which are not idempotent, i.e. `POST` and `PATCH`,
+/// are only retried if the server cannot have processed them: when no connection could be
+/// established or the server responded with `429`.
+///
+/// A `Retry-After` header is honoured, unless it asks for a delay longer than the maximum
+/// backoff, in which case the response is returned as is.
+#[derive(Debug, Clone)]
+pub struct ExponentialBackoff {
+    max_attempts: u32,
+    min_backoff: Duration,
+    max_backoff: Duration,
+    jitter: f64,
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_ATTEMPTS)
+    }
+}
+
+impl ExponentialBackoff {
+    /// Create a new instance, sending each request at most `max_attempts` times.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            min_backoff: DEFAULT_MIN_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            jitter: 0.5,
+        }
+    }
+
+    /// Specify the bounds of the delay between attempts, defaults to 100ms and 20s.
+    pub fn backoff(mut self, min: Duration, max: Duration) -> Self {
+        self.min_backoff = min;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Specify which fraction of the delay is randomised, between 0 and 1, defaults to 0.5.
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns the delay after attempt `number`, before jitter is applied.
+    pub fn delay(&self, number: u32) -> Duration {
+        let factor = 2u32.saturating_pow(number.saturating_sub(1));
+
+        self.min_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    fn with_jitter(&self, delay: Duration) -> Duration {
+        if self.jitter == 0.0 {
+            return delay;
+        }
+
+        // The last four bytes of a v4 UUID are random
+        let random = Uuid::new_v4().as_u128() as u32 as f64 / u32::MAX as f64;
+
+        delay.mul_f64(1.0 - self.jitter * random)
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn retry(&self, attempt: &Attempt<'_>) -> Option<Duration> {
+        if attempt.number >= self.max_attempts {
+            return None;
+        }
+
+        let idempotent = attempt.method.is_idempotent();
+        let retryable = match attempt.outcome {
+            Outcome::Connect => true,
+            Outcome::Timeout | Outcome::Transport => idempotent,
+            Outcome::Response(StatusCode::TOO_MANY_REQUESTS) => true,
+            Outcome::Response(
+                StatusCode::BAD_GATEWAY
+                | StatusCode::SERVICE_UNAVAILABLE
+                | StatusCode::GATEWAY_TIMEOUT,
+            ) => idempotent,
+            Outcome::Response(_) => false,
+        };
+        if !retryable {
+            return None;
+        }
+
+        match attempt.retry_after {
+            Some(delay) if delay > self.max_backoff => None,
+            Some(delay) => Some(delay),
+            None => Some(self.with_jitter(self.delay(attempt.number))),
+        }
+    }
+}
+
+/// A [`RetryPolicy`] which sends every request exactly once.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoRetry;
+
+impl RetryPolicy for NoRetry {
+    fn retry(&self, _attempt: &Attempt<'_>) -> Option<Duration> {
+        None
+    }
+}
+
+/// Send a request with `send`, attempting it again for as long as `policy` allows.
+///
+/// If the last attempt received a response, it is returned regardless of its status.
+#[cfg(any(feature = "hyper-client", feature = "reqwest"))]
+pub(super) async fn send_with_retries<F, Fut>(
+    policy: &dyn RetryPolicy,
+    request: &ConnectRequest,
+    timeout: Option<Duration>,
+    send: F,
+) -> Result<ConnectResponse, Error>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<ConnectResponse, (Outcome, String)>>,
+{
+    let path = request.path_and_query();
+    let mut number = 1;
+
+    loop {
+        let started = Instant::now();
+        let response = match timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, send()).await {
+                Ok(response) => response,
+                Err(_) => Err((
+                    Outcome::Timeout,
+                    format!("request timed out after {:?}", timeout),
+                )),
+            },
+            None => send().await,
+        };
+
+        let (outcome, retry_after) = match &response {
+            Ok(response) => (
+                Outcome::Response(response.status),
+                retry_after(&response.headers),
+            ),
+            Err((outcome, _)) => (*outcome, None),
+        };
+        let attempt = Attempt {
+            method: request.method(),
+            path: &path,
+            number,
+            outcome,
+            retry_after,
+            elapsed: started.elapsed(),
+        };
+        let delay = if outcome.is_success() {
+            None
+        } else {
+            policy.retry(&attempt)
+        };
+        policy.on_attempt(&attempt, delay);
+
+        match (response, delay) {
+            (Ok(response), None) => return Ok(response),
+            (Err((outcome, message)), None) => {
+                let message = format!("request failed after {} attempt(s): {}", number, message);
+                let cause = CustomError::new(&message);
+
+                return Err(match outcome {
+                    Outcome::Timeout => Error::new_timeout_error(cause),
+                    _ => Error::new_network_error(cause),
+                });
+            }
+            (_, Some(delay)) => {
+                tokio::time::sleep(delay).await;
+                number += 1;
+            }
+        }
+    }
+}
+
+/// Parse a `Retry-After` header, given either in seconds or as an HTTP date.
+#[cfg(any(feature = "hyper-client", feature = "reqwest"))]
+pub(super) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = date.signed_duration_since(chrono::Utc::now());
+
+    Some(delay.to_std().unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{retry_after, Attempt, ExponentialBackoff, Outcome, RetryPolicy};
+    use crate::client::{ConnectRequest, ConnectResponse, Method, NoRetry};
+    use http::{header::RETRY_AFTER, HeaderMap, StatusCode};
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    fn attempt(method: Method, number: u32, outcome: Outcome) -> Attempt<'static> {
+        Attempt {
+            method,
+            path: "v1/vaults",
+            number,
+            outcome,
+            retry_after: None,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn retries_idempotent_requests() {
+        let policy = ExponentialBackoff::new(3).jitter(0.0);
+        let unavailable = Outcome::Response(StatusCode::SERVICE_UNAVAILABLE);
+
+        assert_eq!(
+            policy.retry(&attempt(Method::Get, 1, unavailable)),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            policy.retry(&attempt(Method::Get, 2, Outcome::Timeout)),
+            Some(Duration::from_millis(200))
+        );
+        assert_eq!(policy.retry(&attempt(Method::Get, 3, unavailable)), None);
+        assert_eq!(
+            policy.retry(&attempt(
+                Method::Get,
+                1,
+                Outcome::Response(StatusCode::NOT_FOUND)
+            )),
+            None
+        );
+    }
+
+    #[test]
+    fn never_blindly_retries_post() {
+        let policy = ExponentialBackoff::new(3).jitter(0.0);
+
+        assert_eq!(
+            policy.retry(&attempt(
+                Method::Post,
+                1,
+                Outcome::Response(StatusCode::BAD_GATEWAY)
+            )),
+            None
+        );
+        assert_eq!(
+            policy.retry(&attempt(Method::Post, 1, Outcome::Timeout)),
+            None
+        );
+        assert!(policy
+            .retry(&attempt(Method::Post, 1, Outcome::Connect))
+            .is_some());
+        assert!(policy
+            .retry(&attempt(
+                Method::Post,
+                1,
+                Outcome::Response(StatusCode::TOO_MANY_REQUESTS)
+            ))
+            .is_some());
+    }
+
+    #[test]
+    fn honours_retry_after() {
+        let policy = ExponentialBackoff::new(3).backoff(Duration::ZERO, Duration::from_secs(10));
+        let mut limited = attempt(
+            Method::Get,
+            1,
+            Outcome::Response(StatusCode::TOO_MANY_REQUESTS),
+        );
+
+        limited.retry_after = Some(Duration::from_secs(2));
+        assert_eq!(policy.retry(&limited), Some(Duration::from_secs(2)));
+
+        limited.retry_after = Some(Duration::from_secs(60));
+        assert_eq!(policy.retry(&limited), None);
+    }
+
+    #[test]
+    fn applies_jitter_within_bounds() {
+        let policy = ExponentialBackoff::new(10)
+            .backoff(Duration::from_secs(1), Duration::from_secs(4))
+            .jitter(0.5);
+
+        for number in 1..10 {
+            let delay = policy
+                .retry(&attempt(Method::Get, number, Outcome::Transport))
+                .unwrap();
+            let max = policy.delay(number);
+
+            assert!(delay <= max && delay >= max / 2);
+        }
+        assert_eq!(policy.delay(9), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn parses_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, "120".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(120)));
+
+        headers.insert(
+            RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+    }
+
+    /// Respond with `statuses` in order, repeating the last one.
+    async fn respond(
+        statuses: &[u16],
+        requests: &AtomicUsize,
+    ) -> Result<ConnectResponse, (Outcome, String)> {
+        let index = requests.fetch_add(1, Ordering::SeqCst);
+        let status = StatusCode::from_u16(statuses[index.min(statuses.len() - 1)]).unwrap();
+
+        let mut response = ConnectResponse::new(status, "{}");
+        response.headers.insert(RETRY_AFTER, "0".parse().unwrap());
+        Ok(response)
+    }
+
+    #[tokio::test]
+    async fn retries_until_success() {
+        let requests = AtomicUsize::new(0);
+        let request = ConnectRequest::get(&["v1", "vaults"]);
+
+        let response =
+            super::send_with_retries(&ExponentialBackoff::new(5), &request, None, || {
+                respond(&[503, 503, 200], &requests)
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn returns_last_response() {
+        let requests = AtomicUsize::new(0);
+        let request = ConnectRequest::post(&["v1", "vaults", "vault", "items"]);
+
+        let response =
+            super::send_with_retries(&ExponentialBackoff::new(5), &request, None, || {
+                respond(&[503, 200], &requests)
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn reports_timeouts() {
+        let request = ConnectRequest::get(&["v1", "vaults"]);
+
+        let err = super::send_with_retries(&NoRetry, &request, Some(Duration::ZERO), || async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Err((Outcome::Transport, "unreachable".to_string()))
+        })
+        .await
+        .unwrap_err();
+
+        assert!(err.is_timeout());
+    }
+
+    /// Serve `statuses` in order, repeating the last one, and count the requests received.
+    #[cfg(feature = "hyper-client")]
+    fn serve(statuses: &'static [u16]) -> (std::net::SocketAddr, std::sync::Arc<AtomicUsize>) {
+        use hyper::{
+            service::{make_service_fn, service_fn},
+            Body, Response, Server,
+        };
+        use std::{convert::Infallible, sync::Arc};
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        let make_service = make_service_fn(move |_| {
+            let counter = counter.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |_req| {
+                    let index = counter.fetch_add(1, Ordering::SeqCst);
+                    let status = statuses[index.min(statuses.len() - 1)];
+                    async move {
+                        Response::builder()
+                            .status(status)
+                            .header(RETRY_AFTER, "0")
+                            .body(Body::from("{}"))
+                    }
+                }))
+            }
+        });
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        (addr, requests)
+    }
+
+    #[cfg(feature = "hyper-client")]
+    #[tokio::test]
+    async fn client_retries_unavailable_server() {
+        use crate::client::{ClientBuilder, HTTPClient};
+
+        let (addr, requests) = serve(&[503, 503, 200]);
+        let client = ClientBuilder::new("token", &format!("http://{}", addr))
+            .build()
+            .unwrap();
+
+        let (_, json) = client
+            .send_request::<serde_json::Value>(ConnectRequest::get(&["v1", "vaults"]))
+            .await
+            .unwrap();
+
+        assert_eq!(json, serde_json::json!({}));
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[cfg(feature = "hyper-client")]
+    #[tokio::test]
+    async fn client_does_not_retry_post() {
+        use crate::client::{ClientBuilder, HTTPClient};
+
+        let (addr, requests) = serve(&[503, 200]);
+        let client = ClientBuilder::new("token", &format!("http://{}", addr))
+            .build()
+            .unwrap();
+
+        let result = client
+            .send_request::<serde_json::Value>(
+                ConnectRequest::post(&["v1", "vaults", "vault", "items"]).body("{}"),
+            )
+            .await;
+
+        assert_eq!(
+            result.unwrap_err().status(),
+            Some(StatusCode::SERVICE_UNAVAILABLE)
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,288 @@
+//! TLS configuration
+
+use crate::error::{CustomError, Error};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use log::debug;
+use ring::digest::{digest, SHA256};
+use rustls::{
+    client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier},
+    Certificate, ClientConfig, PrivateKey, RootCertStore, ServerName,
+};
+use std::{sync::Arc, time::SystemTime};
+
+/// TLS options collected by the [`ClientBuilder`](super::ClientBuilder)
+#[derive(Clone)]
+pub(super) struct TlsOptions {
+    pub(super) built_in_roots: bool,
+    pub(super) root_certificates_pem: Vec<Vec<u8>>,
+    pub(super) identity_pem: Option<(Vec<u8>, Vec<u8>)>,
+    pub(super) spki_pins: Vec<String>,
+}
+
+/// Leaves out the certificates, and the private key of the identity
+impl std::fmt::Debug for TlsOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsOptions")
+            .field("built_in_roots", &self.built_in_roots)
+            .field("root_certificates", &self.root_certificates_pem.len())
+            .field(
+                "identity",
+                &self.identity_pem.as_ref().map(|_| super::REDACTED),
+            )
+            .field("spki_pins", &self.spki_pins)
+            .finish()
+    }
+}
+
+impl Default for TlsOptions {
+    fn default() -> Self {
+        Self {
+            built_in_roots: true,
+            root_certificates_pem: vec![],
+            identity_pem: None,
+            spki_pins: vec![],
+        }
+    }
+}
+
+impl TlsOptions {
+    /// Build a rustls configuration from the collected options.
+    pub(super) fn client_config(&self) -> Result<ClientConfig, Error> {
+        let mut roots = RootCertStore::empty();
+
+        if self.built_in_roots {
+            match rustls_native_certs::load_native_certs() {
+                Ok(certs) => {
+                    let certs: Vec<Vec<u8>> = certs.iter().map(|cert| cert.to_vec()).collect();
+                    let (added, ignored) = roots.add_parsable_certificates(&certs);
+                    debug!(
+                        "Loaded {} native root certificates, {} ignored",
+                        added, ignored
+                    );
+                }
+                Err(err) => debug!("Unable to load native root certificates: {}", err),
+            }
+        }
+
+        for pem in &self.root_certificates_pem {
+            let certs = read_certificates(pem)?;
+            if certs.is_empty() {
+                return Err(config_error("no certificates found in CA bundle"));
+            }
+
+            for cert in certs {
+                roots.add(&cert).map_err(Error::new_config_error)?;
+            }
+        }
+
+        let pins = self
+            .spki_pins
+            .iter()
+            .map(|pin| decode_pin(pin))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let verifier = WebPkiVerifier::new(roots, None);
+        let verifier: Arc<dyn ServerCertVerifier> = if pins.is_empty() {
+            Arc::new(verifier)
+        } else {
+            Arc::new(PinnedVerifier {
+                inner: verifier,
+                pins,
+            })
+        };
+        let builder = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(verifier);
+
+        match &self.identity_pem {
+            Some((cert_pem, key_pem)) => {
+                let certs = read_certificates(cert_pem)?;
+                if certs.is_empty() {
+                    return Err(config_error("no certificates found in client identity"));
+                }
+                let key = read_private_key(key_pem)?;
+
+                builder
+                    .with_single_cert(certs, key)
+                    .map_err(Error::new_config_error)
+            }
+            None => Ok(builder.with_no_client_auth()),
+        }
+    }
+}
+
+/// Verifies the certificate chain as usual, and additionally requires the SPKI of one of the
+/// certificates in the chain to match a pinned SHA-256 hash.
+struct PinnedVerifier {
+    inner: WebPkiVerifier,
+    pins: Vec<Vec<u8>>,
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        server_name: &ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            scts,
+            ocsp_response,
+            now,
+        )?;
+
+        let pinned = std::iter::once(end_entity)
+            .chain(intermediates)
+            .filter_map(|cert| subject_public_key_info(&cert.0))
+            .any(|spki| {
+                let hash = digest(&SHA256, spki);
+                self.pins.iter().any(|pin| pin.as_slice() == hash.as_ref())
+            });
+
+        if pinned {
+            Ok(verified)
+        } else {
+            Err(rustls::Error::General(
+                "no certificate in the chain matches a pinned public key".to_string(),
+            ))
+        }
+    }
+}
+
+fn read_certificates(pem: &[u8]) -> Result<Vec<Certificate>, Error> {
+    let certs = rustls_pemfile::certs(&mut &pem[..]).map_err(Error::new_config_error)?;
+
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn read_private_key(pem: &[u8]) -> Result<PrivateKey, Error> {
+    let items = rustls_pemfile::read_all(&mut &pem[..]).map_err(Error::new_config_error)?;
+
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| config_error("no private key found in client identity"))
+}
+
+/// Decode a pin given as Base64, optionally prefixed with `sha256/`.
+fn decode_pin(pin: &str) -> Result<Vec<u8>, Error> {
+    let encoded = pin.strip_prefix("sha256/").unwrap_or(pin);
+    let hash = STANDARD
+        .decode(encoded)
+        .map_err(|_| config_error(&format!("invalid SPKI pin: {}", pin)))?;
+
+    if hash.len() != SHA256.output_len {
+        return Err(config_error(&format!("invalid SPKI pin length: {}", pin)));
+    }
+
+    Ok(hash)
+}
+
+fn config_error(message: &str) -> Error {
+    Error::new_config_error(CustomError::new(message))
+}
+
+/// Extract the DER encoded SubjectPublicKeyInfo of an X.509 certificate.
+fn subject_public_key_info(cert: &[u8]) -> Option<&[u8]> {
+    let (_, certificate, _) = read_der(cert)?;
+    let (_, tbs_certificate, _) = read_der(certificate)?;
+
+    let mut rest = tbs_certificate;
+    // The version is optional and explicitly tagged
+    if rest.first() == Some(&0xa0) {
+        rest = read_der(rest)?.2;
+    }
+    // serialNumber, signature, issuer, validity and subject
+    for _ in 0..5 {
+        rest = read_der(rest)?.2;
+    }
+
+    let (spki, _, _) = read_der(rest)?;
+    Some(spki)
+}
+
+/// Read a single DER element, returning the whole element, its contents and the remaining input.
+fn read_der(input: &[u8]) -> Option<(&[u8], &[u8], &[u8])> {
+    let first = *input.get(1)?;
+    let (length, header) = if first < 0x80 {
+        (first as usize, 2)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 {
+            return None;
+        }
+        let length = input
+            .get(2..2 + count)?
+            .iter()
+            .fold(0usize, |acc, byte| (acc << 8) | *byte as usize);
+        (length, 2 + count)
+    };
+
+    let end = header.checked_add(length)?;
+    let element = input.get(..end)?;
+
+    Some((element, &element[header..], &input[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_pin, subject_public_key_info, TlsOptions};
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use ring::digest::{digest, SHA256};
+
+    // Self-signed test certificate, generated with
+    // `openssl req -x509 -newkey ec -pkeyopt ec_paramgen_curve:P-256 -nodes -subj /CN=connect`
+    const CERTIFICATE_PEM: &str = include_str!("../../tests/fixtures/connect.crt");
+    const PRIVATE_KEY_PEM: &str = include_str!("../../tests/fixtures/connect.key");
+    const SPKI_SHA256: &str = include_str!("../../tests/fixtures/connect.spki");
+
+    #[test]
+    fn extracts_subject_public_key_info() {
+        let certs = rustls_pemfile::certs(&mut CERTIFICATE_PEM.as_bytes()).unwrap();
+        let spki = subject_public_key_info(&certs[0]).unwrap();
+
+        assert_eq!(STANDARD.encode(digest(&SHA256, spki)), SPKI_SHA256.trim());
+    }
+
+    #[test]
+    fn builds_config_with_custom_ca_and_identity() {
+        let options = TlsOptions {
+            built_in_roots: false,
+            root_certificates_pem: vec![CERTIFICATE_PEM.as_bytes().to_vec()],
+            identity_pem: Some((
+                CERTIFICATE_PEM.as_bytes().to_vec(),
+                PRIVATE_KEY_PEM.as_bytes().to_vec(),
+            )),
+            spki_pins: vec![format!("sha256/{}", SPKI_SHA256.trim())],
+        };
+
+        assert!(options.client_config().is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_ca_bundle() {
+        let options = TlsOptions {
+            root_certificates_pem: vec![b"not a certificate".to_vec()],
+            ..TlsOptions::default()
+        };
+
+        assert!(options.client_config().is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_pin() {
+        assert!(decode_pin("sha256/not-base64!").is_err());
+        assert!(decode_pin(&STANDARD.encode([0u8; 16])).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 //! Error and Result module.
 
-use hyper::{header::InvalidHeaderValue, StatusCode};
-use regex::Regex;
+use http::{header::InvalidHeaderValue, StatusCode};
+use serde::Deserialize;
 use std::{
     error::Error as StdError,
     fmt::{self, Display},
@@ -71,7 +71,6 @@ impl Error {
     }
 
     #[allow(dead_code)]
-
     pub(crate) fn find_source<E: StdError + 'static>(&self) -> Option<&E> {
         let mut cause = self.source();
         while let Some(err) = cause {
@@ -84,6 +83,7 @@ impl Error {
         None
     }
 
+    #[allow(dead_code)]
     pub(super) fn new_network_error<E: Into<Cause>>(cause: E) -> Self {
         Error::new(Kind::NetworkError).with(cause)
     }
@@ -92,19 +92,169 @@ impl Error {
         Error::new(Kind::ParsingError).with(cause)
     }
 
+    pub(super) fn new_config_error<E: Into<Cause>>(cause: E) -> Self {
+        Error::new(Kind::ConfigError).with(cause)
+    }
+
+    #[allow(dead_code)]
+    pub(super) fn new_timeout_error<E: Into<Cause>>(cause: E) -> Self {
+        Error::new(Kind::Timeout).with(cause)
+    }
+
     #[allow(dead_code)]
     pub(super) fn new_retry_error<E: Into<Cause>>(cause: E) -> Self {
         Error::new(Kind::RetryError).with(cause)
     }
 
+    /// Decodes the body of an unsuccessful response to the request for `path` as a
+    /// [`ConnectAPIError`] where possible.
+    pub(super) fn new_response_error(err: RequestNotSuccessful, path: &[String]) -> Self {
+        match ConnectAPIError::from_body(&err.body, path) {
+            Some(connect_error) => Error::new_connect_error(connect_error),
+            None => Error::new(Kind::RequestNotSuccessful(err)),
+        }
+    }
+
     pub(super) fn new_connect_error(err: ConnectAPIError) -> Self {
         Error::new(Kind::ConnectAPIError(err))
     }
 
+    pub(super) fn new_not_found_error(message: &str) -> Self {
+        Error::new(Kind::NotFound(message.to_string()))
+    }
+
+    pub(super) fn new_unresolved_error(missing: Vec<(String, Error)>) -> Self {
+        Error::new(Kind::UnresolvedReferences(missing))
+    }
+
+    #[allow(dead_code)]
     pub(super) fn new_internal_error() -> Self {
         Error::new(Kind::InternalError)
     }
 
+    /// The category of this error.
+    ///
+    /// ```
+    /// use connect_1password::error::{Error, ErrorKind};
+    ///
+    /// fn http_status(err: &Error) -> u16 {
+    ///     match err.kind() {
+    ///         ErrorKind::Api | ErrorKind::Http => err.status().map_or(500, |status| status.as_u16()),
+    ///         ErrorKind::Timeout => 504,
+    ///         ErrorKind::Network => 502,
+    ///         _ => 500,
+    ///     }
+    /// }
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        match &self.inner.kind {
+            Kind::ConfigError => ErrorKind::Config,
+            #[cfg(feature = "hyper-client")]
+            Kind::HyperError(_) => ErrorKind::Network,
+            Kind::NetworkError | Kind::RetryError => ErrorKind::Network,
+            Kind::Timeout => ErrorKind::Timeout,
+            Kind::ParsingError | Kind::SerdeJsonError(_) | Kind::Utf8Error => ErrorKind::Parsing,
+            Kind::ConnectAPIError(_) => ErrorKind::Api,
+            Kind::RequestNotSuccessful(_) => ErrorKind::Http,
+            Kind::NotFound(_) => ErrorKind::NotFound,
+            Kind::UnresolvedReferences(_) => ErrorKind::Unresolved,
+            Kind::CustomError(_)
+            | Kind::HyperHttpError(_)
+            | Kind::InternalError
+            | Kind::InvalidHeaderValue
+            | Kind::NotImplementedError => ErrorKind::Other,
+        }
+    }
+
+    /// The HTTP status of the response, if the server responded with an unsuccessful status.
+    pub fn status(&self) -> Option<StatusCode> {
+        match &self.inner.kind {
+            Kind::ConnectAPIError(err) => StatusCode::from_u16(err.status).ok(),
+            Kind::RequestNotSuccessful(err) => Some(err.status),
+            _ => None,
+        }
+    }
+
+    /// The raw body of the response, if the server responded with an unsuccessful status.
+    pub fn body(&self) -> Option<&str> {
+        match &self.inner.kind {
+            Kind::ConnectAPIError(err) => err.body(),
+            Kind::RequestNotSuccessful(err) => Some(&err.body),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the vault, item or other resource does not exist, whether the server
+    /// responded with a `404` or the client found no match, see [`ErrorKind::NotFound`].
+    pub fn is_not_found(&self) -> bool {
+        self.kind() == ErrorKind::NotFound || self.status() == Some(StatusCode::NOT_FOUND)
+    }
+
+    /// Returns true if the token is missing, invalid or expired.
+    pub fn is_unauthorized(&self) -> bool {
+        self.status() == Some(StatusCode::UNAUTHORIZED)
+    }
+
+    /// Returns true if a request timed out.
+    pub fn is_timeout(&self) -> bool {
+        self.kind() == ErrorKind::Timeout
+    }
+
+    /// Returns true if the same request may succeed when it is sent again later, i.e. after a
+    /// timeout, a network failure, or a `429`, `502`, `503` or `504` response.
+    pub fn is_retryable(&self) -> bool {
+        match self.kind() {
+            ErrorKind::Network | ErrorKind::Timeout => true,
+            _ => matches!(
+                self.status(),
+                Some(
+                    StatusCode::TOO_MANY_REQUESTS
+                        | StatusCode::BAD_GATEWAY
+                        | StatusCode::SERVICE_UNAVAILABLE
+                        | StatusCode::GATEWAY_TIMEOUT
+                )
+            ),
+        }
+    }
+
+    /// Returns true if the item being updated was changed on the server since it was fetched.
+    pub fn is_version_conflict(&self) -> bool {
+        self.connect_error()
+            .is_some_and(|err| err.code == ConnectErrorCode::Conflict)
+    }
+
+    /// The error reported by the Connect API, if the request was rejected by the server.
+    ///
+    /// ```
+    /// use connect_1password::error::{ConnectErrorCode, Error};
+    ///
+    /// fn describe(err: &Error) -> &'static str {
+    ///     match err.connect_error().map(|err| err.code) {
+    ///         Some(ConnectErrorCode::ItemNotFound) => "no such item",
+    ///         Some(ConnectErrorCode::Unauthorized) => "check the token",
+    ///         _ => "something went wrong",
+    ///     }
+    /// }
+    /// ```
+    pub fn connect_error(&self) -> Option<&ConnectAPIError> {
+        match &self.inner.kind {
+            Kind::ConnectAPIError(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// The secret references which could not be resolved, with the reason for each, if this
+    /// error lists them.
+    ///
+    /// Such an error is of kind [`ErrorKind::Unresolved`] and has no status, look at the errors
+    /// of the references instead.
+    pub fn unresolved(&self) -> Option<&[(String, Error)]> {
+        match &self.inner.kind {
+            Kind::UnresolvedReferences(missing) => Some(missing),
+            _ => None,
+        }
+    }
+
     /// The error's standalone message, without the message from the source.
     pub fn message(&self) -> impl fmt::Display + '_ {
         self.description()
@@ -112,11 +262,14 @@ impl Error {
 
     fn description(&self) -> String {
         match &self.inner.kind {
+            #[cfg(feature = "hyper-client")]
             Kind::HyperError(_) => "this is a Hyper related error!".to_string(),
             Kind::HyperHttpError(_) => "this is a Hyper HTTP related error!".to_string(),
+            Kind::ConfigError => "invalid client configuration".to_string(),
             Kind::InternalError => "internal error".to_string(),
             Kind::InvalidHeaderValue => "invalid header value".to_string(),
             Kind::NetworkError => "network error".to_string(),
+            Kind::Timeout => "request timed out".to_string(),
             Kind::NotImplementedError => "not implemented error".to_string(),
             Kind::ParsingError => "parsing error".to_string(),
             Kind::RetryError => "retry error".to_string(),
@@ -128,6 +281,23 @@ impl Error {
             Kind::CustomError(err) => {
                 format!("Error: {}", err)
             }
+            Kind::NotFound(message) => message.clone(),
+            Kind::UnresolvedReferences(missing) => {
+                let details = missing
+                    .iter()
+                    .map(|(reference, err)| match err.connect_error() {
+                        Some(connect_error) => format!("{} ({})", reference, connect_error.message),
+                        None => format!("{} ({})", reference, err),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                format!(
+                    "Unable to resolve {} secret reference(s): {}",
+                    missing.len(),
+                    details
+                )
+            }
             Kind::ConnectAPIError(err) => {
                 format!("Connect API error: {}", err)
             }
@@ -135,6 +305,32 @@ impl Error {
     }
 }
 
+/// Describes the category of an [`Error`]
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The client could not be built from the given configuration
+    Config,
+    /// The server could not be reached, or the connection failed
+    Network,
+    /// The request did not complete within the configured timeout
+    Timeout,
+    /// The response could not be decoded
+    Parsing,
+    /// The Connect API rejected the request, see [`Error::connect_error`]
+    Api,
+    /// The server responded with an unsuccessful status and a body which is not a Connect API
+    /// error, i.e. from a proxy
+    Http,
+    /// The vault, item or field was not among those returned by the server, i.e. when resolving
+    /// a secret reference by name. A `404` response is [`ErrorKind::Api`] instead.
+    NotFound,
+    /// Secret references could not be resolved, see [`Error::unresolved`] for the reason of each
+    Unresolved,
+    /// Any other error
+    Other,
+}
+
 /// Wrapper type which contains a failed request's status code and body.
 #[derive(Debug)]
 pub struct RequestNotSuccessful {
@@ -159,23 +355,103 @@ impl Display for RequestNotSuccessful {
     }
 }
 
-/// Wrapper type which contains Vault errors.
-#[derive(Debug)]
+/// Describes the known error conditions reported by the Connect API
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectErrorCode {
+    /// The token is missing, invalid or expired (`401`)
+    Unauthorized,
+    /// The token has no access to the vault or the action (`403`)
+    Forbidden,
+    /// The vault does not exist or is not in scope of the token (`404`)
+    VaultNotFound,
+    /// The item does not exist (`404`)
+    ItemNotFound,
+    /// Any other resource does not exist (`404`)
+    NotFound,
+    /// The request, i.e. an item or patch, was rejected as invalid (`400`)
+    ValidationFailed,
+    /// The item was modified since it was last fetched (`409`)
+    Conflict,
+    /// Too many requests were made (`429`)
+    RateLimited,
+    /// The server failed to handle the request (`5xx`)
+    ServerError,
+    /// Any other status
+    Other,
+}
+
+impl ConnectErrorCode {
+    /// Classify an error from its status and the path segments of the request, i.e. a `404` for
+    /// `["v1", "vaults", vault_id]` is [`ConnectErrorCode::VaultNotFound`].
+    pub fn from_response(status: u16, path: &[String]) -> Self {
+        match status {
+            400 => Self::ValidationFailed,
+            401 => Self::Unauthorized,
+            403 => Self::Forbidden,
+            404 => {
+                let path: Vec<&str> = path.iter().map(String::as_str).collect();
+                match path.as_slice() {
+                    ["v1", "vaults", _] | ["v1", "vaults", _, "items"] => Self::VaultNotFound,
+                    ["v1", "vaults", _, "items", _] | ["v1", "vaults", _, "items", _, "files"] => {
+                        Self::ItemNotFound
+                    }
+                    _ => Self::NotFound,
+                }
+            }
+            409 => Self::Conflict,
+            429 => Self::RateLimited,
+            500..=599 => Self::ServerError,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// An error reported by the Connect API, decoded from its `{"status": .., "message": ..}` body.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ConnectAPIError {
+    /// The known error condition.
+    pub code: ConnectErrorCode,
+    /// Status code returned by the HTTP call.
+    pub status: u16,
     /// Error message from the API.
     pub message: String,
-    /// Status code returned by the HTTP call.
-    pub status: String,
+    body: Option<String>,
 }
 
 impl ConnectAPIError {
-    /// Create a new unsuccessful request error.
-    pub fn new(status: String, message: &str) -> Self {
+    /// Create a new error, classifying it from its status alone.
+    pub fn new(status: u16, message: &str) -> Self {
         Self {
+            code: ConnectErrorCode::from_response(status, &[]),
             status,
             message: message.to_string(),
+            body: None,
         }
     }
+
+    /// The raw response body this error was decoded from.
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
+    /// Decode the error response body to a request for `path`, returns `None` if it is not a
+    /// Connect API error.
+    pub fn from_body(body: &str, path: &[String]) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct ErrorBody {
+            status: u16,
+            message: String,
+        }
+
+        let decoded: ErrorBody = serde_json::from_str(body).ok()?;
+
+        Some(Self {
+            code: ConnectErrorCode::from_response(decoded.status, path),
+            body: Some(body.to_string()),
+            ..Self::new(decoded.status, &decoded.message)
+        })
+    }
 }
 
 impl StdError for ConnectAPIError {}
@@ -210,8 +486,8 @@ impl Display for CustomError {
     }
 }
 
-impl From<hyper::http::Error> for CustomError {
-    fn from(err: hyper::http::Error) -> Self {
+impl From<http::Error> for CustomError {
+    fn from(err: http::Error) -> Self {
         Self::new(err.to_string().as_str())
     }
 }
@@ -234,10 +510,14 @@ pub(super) enum Kind {
     CustomError(CustomError),
 
     /// The failure was due to a Hyper error
+    #[cfg(feature = "hyper-client")]
     HyperError(hyper::Error),
 
-    /// The failure was due to a Hyper error
-    HyperHttpError(hyper::http::Error),
+    /// The failure was due to an invalid HTTP request or response
+    HyperHttpError(http::Error),
+
+    /// The client could not be built from the given configuration.
+    ConfigError,
 
     InternalError,
 
@@ -246,6 +526,9 @@ pub(super) enum Kind {
     /// The failure was due to the network client not working properly.
     NetworkError,
 
+    /// The request did not complete within the configured timeout.
+    Timeout,
+
     NotImplementedError,
 
     ParsingError,
@@ -259,17 +542,27 @@ pub(super) enum Kind {
     Utf8Error,
 
     ConnectAPIError(ConnectAPIError),
+
+    /// The client found no vault, item or field matching a name.
+    NotFound(String),
+
+    /// Secret references could not be resolved, each with the reason.
+    UnresolvedReferences(Vec<(String, Error)>),
 }
 
 impl fmt::Display for Kind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            #[cfg(feature = "hyper-client")]
             &Self::HyperError(_) => {
                 write!(f, "HyperError")
             }
             &Self::HyperHttpError(_) => {
                 write!(f, "HyperHttpError")
             }
+            Self::ConfigError => {
+                write!(f, "ConfigError")
+            }
             Self::InternalError => {
                 write!(f, "InternalError")
             }
@@ -279,6 +572,9 @@ impl fmt::Display for Kind {
             Self::NetworkError => {
                 write!(f, "NetworkError")
             }
+            Self::Timeout => {
+                write!(f, "Timeout")
+            }
             Self::NotImplementedError => {
                 write!(f, "NotImplementedError")
             }
@@ -300,6 +596,12 @@ impl fmt::Display for Kind {
             &Self::ConnectAPIError(_) => {
                 write!(f, "ConnectAPIError")
             }
+            &Self::NotFound(_) => {
+                write!(f, "NotFound")
+            }
+            &Self::UnresolvedReferences(_) => {
+                write!(f, "UnresolvedReferences")
+            }
             &Self::CustomError(_) => {
                 write!(f, "CustomError")
             }
@@ -307,14 +609,15 @@ impl fmt::Display for Kind {
     }
 }
 
+#[cfg(feature = "hyper-client")]
 impl From<hyper::Error> for Error {
     fn from(err: hyper::Error) -> Self {
         Error::new(Kind::HyperError(err))
     }
 }
 
-impl From<hyper::http::Error> for Error {
-    fn from(err: hyper::http::Error) -> Self {
+impl From<http::Error> for Error {
+    fn from(err: http::Error) -> Self {
         Error::new(Kind::HyperHttpError(err))
     }
 }
@@ -325,9 +628,10 @@ impl From<InvalidHeaderValue> for Error {
     }
 }
 
+/// Decodes the body as a [`ConnectAPIError`] where possible, without classifying 404s by path.
 impl From<RequestNotSuccessful> for Error {
     fn from(err: RequestNotSuccessful) -> Self {
-        Error::new(Kind::RequestNotSuccessful(err))
+        Error::new_response_error(err, &[])
     }
 }
 
@@ -349,44 +653,96 @@ impl From<CustomError> for Error {
     }
 }
 
-/// Defines an error from the 1Password Connect API
-#[allow(dead_code)]
-#[derive(Debug)]
-pub struct OPError {
-    pub(super) status_code: Option<u16>,
-    pub(super) captures: Option<Vec<String>>,
-}
+#[cfg(test)]
+mod tests {
+    use super::{ConnectErrorCode, Error, ErrorKind, RequestNotSuccessful};
+    use http::StatusCode;
+
+    #[test]
+    fn decodes_connect_error_body() {
+        let path = ["v1", "vaults", "vault", "items", "4a6c"].map(String::from);
+        let err = Error::new_response_error(
+            RequestNotSuccessful::new(
+                StatusCode::NOT_FOUND,
+                r#"{"status":404,"message":"item 4a6c not found"}"#.to_string(),
+            ),
+            &path,
+        );
+
+        let connect_error = err.connect_error().unwrap();
+        assert_eq!(connect_error.code, ConnectErrorCode::ItemNotFound);
+        assert_eq!(connect_error.status, 404);
+        assert_eq!(connect_error.message, "item 4a6c not found");
+
+        assert_eq!(err.kind(), ErrorKind::Api);
+        assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+        assert_eq!(
+            err.body(),
+            Some(r#"{"status":404,"message":"item 4a6c not found"}"#)
+        );
+        assert!(err.is_not_found());
+        assert!(!err.is_unauthorized());
+        assert!(!err.is_retryable());
+    }
 
-/// Handle Connect API error response
-pub fn process_connect_error_response(err_message: String) -> Result<OPError, Error> {
-    let input_re = Regex::new(r#"(StatusCode):\s+(\d+)"#).unwrap();
-
-    // Execute the Regex
-    let captures = input_re.captures(&err_message).map(|captures| {
-        captures
-            .iter() // All the captured groups
-            .skip(1) // Skipping the complete match
-            .flatten() // Ignoring all empty optional matches
-            .map(|c| c.as_str()) // Grab the original strings
-            .collect::<Vec<_>>() // Create a vector
-    });
-
-    dbg!(&captures);
-
-    // Match against the captured values as a slice
-    let status_code: Option<u16> = match captures.as_deref() {
-        Some(["StatusCode", x]) => {
-            let x = x.parse().expect("can't parse number");
-            Some(x)
-        }
-        _ => None,
-    };
+    #[test]
+    fn classifies_connect_errors() {
+        let code = |status, path: &[&str]| {
+            let path: Vec<String> = path.iter().map(|segment| segment.to_string()).collect();
+            ConnectErrorCode::from_response(status, &path)
+        };
+        let item = ["v1", "vaults", "vault", "items", "item"];
+
+        assert_eq!(code(401, &item), ConnectErrorCode::Unauthorized);
+        assert_eq!(code(403, &item), ConnectErrorCode::Forbidden);
+        assert_eq!(
+            code(404, &["v1", "vaults", "vault"]),
+            ConnectErrorCode::VaultNotFound
+        );
+        assert_eq!(
+            code(404, &["v1", "vaults", "vault", "items"]),
+            ConnectErrorCode::VaultNotFound
+        );
+        assert_eq!(code(404, &item), ConnectErrorCode::ItemNotFound);
+        assert_eq!(
+            code(404, &["v1", "vaults", "vault", "items", "item", "files"]),
+            ConnectErrorCode::ItemNotFound
+        );
+        assert_eq!(
+            code(
+                404,
+                &["v1", "vaults", "vault", "items", "item", "files", "file"]
+            ),
+            ConnectErrorCode::NotFound
+        );
+        assert_eq!(code(404, &[]), ConnectErrorCode::NotFound);
+        assert_eq!(code(400, &item), ConnectErrorCode::ValidationFailed);
+        assert_eq!(code(409, &item), ConnectErrorCode::Conflict);
+        assert_eq!(code(429, &item), ConnectErrorCode::RateLimited);
+        assert_eq!(code(503, &item), ConnectErrorCode::ServerError);
+        assert_eq!(code(418, &item), ConnectErrorCode::Other);
+    }
 
-    let return_captures: Option<Vec<String>> =
-        captures.map(|b| b.into_iter().map(|c| c.to_owned()).collect::<Vec<_>>());
+    #[test]
+    fn keeps_unknown_error_bodies() {
+        let err: Error =
+            RequestNotSuccessful::new(StatusCode::BAD_GATEWAY, "<html>".to_string()).into();
 
-    Ok(OPError {
-        status_code,
-        captures: return_captures,
-    })
+        assert!(err.connect_error().is_none());
+        assert!(!err.is_version_conflict());
+
+        assert_eq!(err.kind(), ErrorKind::Http);
+        assert_eq!(err.body(), Some("<html>"));
+        assert!(err.is_retryable());
+    }
+
+    #[test]
+    fn classifies_timeouts() {
+        let err = Error::new_timeout_error("request timed out after 1s");
+
+        assert_eq!(err.kind(), ErrorKind::Timeout);
+        assert!(err.is_timeout());
+        assert!(err.is_retryable());
+        assert_eq!(err.status(), None);
+    }
 }
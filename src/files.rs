@@ -0,0 +1,115 @@
+//! Item files
+
+use crate::error::Error;
+use crate::{
+    client::{ByteStream, ConnectRequest, HTTPClient},
+    models::file::FileObject,
+};
+
+/// Get all files attached to an item
+///
+/// When `inline_files` is set, the contents of each file are returned Base64-encoded in
+/// [`FileObject::content`].
+pub async fn list(
+    client: &impl HTTPClient,
+    vault_id: &str,
+    item_id: &str,
+    inline_files: bool,
+) -> Result<(Vec<FileObject>, serde_json::Value), Error> {
+    let request = ConnectRequest::get(&["v1", "vaults", vault_id, "items", item_id, "files"])
+        .query("inline_files", inline_files_param(inline_files));
+
+    let result = client.send_request::<Vec<FileObject>>(request).await?;
+
+    Ok(result)
+}
+
+/// Get file details
+pub async fn get(
+    client: &impl HTTPClient,
+    vault_id: &str,
+    item_id: &str,
+    file_id: &str,
+    inline_files: bool,
+) -> Result<(FileObject, serde_json::Value), Error> {
+    let request =
+        ConnectRequest::get(&["v1", "vaults", vault_id, "items", item_id, "files", file_id])
+            .query("inline_files", inline_files_param(inline_files));
+
+    let result = client.send_request::<FileObject>(request).await?;
+
+    Ok(result)
+}
+
+/// Download the contents of a file as a stream of bytes
+pub async fn content(
+    client: &impl HTTPClient,
+    vault_id: &str,
+    item_id: &str,
+    file_id: &str,
+) -> Result<ByteStream, Error> {
+    let request = ConnectRequest::get(&[
+        "v1", "vaults", vault_id, "items", item_id, "files", file_id, "content",
+    ]);
+
+    let result = client.send_request_stream(request).await?;
+
+    Ok(result)
+}
+
+fn inline_files_param(inline_files: bool) -> &'static str {
+    if inline_files {
+        "true"
+    } else {
+        "false"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::get_test_client;
+    use tokio::test;
+
+    use crate::{
+        files, items,
+        models::item::{FullItem, ItemBuilder, ItemCategory, LoginItem},
+    };
+
+    #[test]
+    async fn list_files_of_item_without_attachments() {
+        let (client, test_vault_id) = get_test_client();
+
+        let item: FullItem = ItemBuilder::new(&test_vault_id, ItemCategory::Login)
+            .title("Test login item without files")
+            .username("Bob")
+            .password("")
+            .build()
+            .unwrap();
+        let (new_item, _) = items::add(&client, item).await.unwrap();
+
+        let (files, _) = files::list(&client, &test_vault_id, &new_item.id, true)
+            .await
+            .unwrap();
+        assert!(files.is_empty());
+
+        items::remove(&client, &test_vault_id, &new_item.id)
+            .await
+            .unwrap();
+    }
+
+    #[should_panic]
+    #[test]
+    async fn get_file_invalid_file() {
+        let (client, test_vault_id) = get_test_client();
+
+        let (items, _) = items::all(&client, &test_vault_id).await.unwrap();
+        let item_id = items
+            .first()
+            .map(|item| item.id.clone())
+            .unwrap_or_default();
+
+        let (_file, _) = files::get(&client, &test_vault_id, &item_id, "foo", false)
+            .await
+            .unwrap();
+    }
+}
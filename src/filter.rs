@@ -0,0 +1,184 @@
+//! SCIM-style filters for server-side queries
+//!
+//! Connect accepts a `filter` query parameter when listing vaults and items, this module compiles
+//! a typed [`Filter`] into that parameter.
+//!
+//! ```
+//! use connect_1password::filter::Filter;
+//!
+//! let filter = Filter::title_eq("Dell XYZ").or(Filter::tag_eq("prod"));
+//! assert_eq!(filter.to_string(), r#"title eq "Dell XYZ" or tag eq "prod""#);
+//! ```
+
+use crate::error::{CustomError, Error};
+use std::fmt;
+
+/// Describes the comparison operators understood by Connect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    /// Equal
+    Eq,
+    /// Starts with
+    Sw,
+    /// Contains
+    Co,
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Eq => write!(f, "eq"),
+            Self::Sw => write!(f, "sw"),
+            Self::Co => write!(f, "co"),
+        }
+    }
+}
+
+/// This is a filter expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    /// Compares an attribute against a value, i.e. `title eq "x"`.
+    Compare {
+        /// Attribute name, such as `title`, `tag` or `name`.
+        attribute: String,
+        /// Comparison operator.
+        operator: Operator,
+        /// Value to compare against, escaped when compiled.
+        value: String,
+    },
+    /// Both filters must match.
+    And(Box<Filter>, Box<Filter>),
+    /// Either filter must match.
+    Or(Box<Filter>, Box<Filter>),
+}
+
+impl Filter {
+    /// Create a new comparison
+    ///
+    /// Fails unless the attribute is made up of letters, digits, `_` and `.`, as it is compiled
+    /// into the filter unquoted.
+    pub fn new(attribute: &str, operator: Operator, value: &str) -> Result<Self, Error> {
+        let valid = !attribute.is_empty()
+            && attribute
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+        if !valid {
+            return Err(Error::new_parsing_error(CustomError::new(&format!(
+                "Invalid filter attribute {:?}",
+                attribute
+            ))));
+        }
+
+        Ok(Self::compare(attribute, operator, value))
+    }
+
+    /// Match items by title
+    pub fn title_eq(title: &str) -> Self {
+        Self::compare("title", Operator::Eq, title)
+    }
+
+    /// Match items by tag
+    pub fn tag_eq(tag: &str) -> Self {
+        Self::compare("tag", Operator::Eq, tag)
+    }
+
+    /// Match vaults by name
+    pub fn name_eq(name: &str) -> Self {
+        Self::compare("name", Operator::Eq, name)
+    }
+
+    /// Combine with another filter, both must match
+    pub fn and(self, other: Filter) -> Self {
+        Self::And(Box::new(self), Box::new(other))
+    }
+
+    /// Combine with another filter, either must match
+    pub fn or(self, other: Filter) -> Self {
+        Self::Or(Box::new(self), Box::new(other))
+    }
+
+    fn compare(attribute: &str, operator: Operator, value: &str) -> Self {
+        Self::Compare {
+            attribute: attribute.to_string(),
+            operator,
+            value: value.to_string(),
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Compare { .. } => write!(f, "{}", self),
+            _ => write!(f, "({})", self),
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Compare {
+                attribute,
+                operator,
+                value,
+            } => write!(f, "{} {} \"{}\"", attribute, operator, escape(value)),
+            Self::And(left, right) => {
+                left.fmt_operand(f)?;
+                write!(f, " and ")?;
+                right.fmt_operand(f)
+            }
+            Self::Or(left, right) => {
+                left.fmt_operand(f)?;
+                write!(f, " or ")?;
+                right.fmt_operand(f)
+            }
+        }
+    }
+}
+
+/// Escape a value for use within a quoted SCIM string.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Filter, Operator};
+
+    #[test]
+    fn compiles_comparison() {
+        assert_eq!(Filter::name_eq("vault").to_string(), r#"name eq "vault""#);
+    }
+
+    #[test]
+    fn escapes_quotes_and_backslashes() {
+        let filter = Filter::title_eq(r#"say "hi" \o/"#);
+
+        assert_eq!(filter.to_string(), r#"title eq "say \"hi\" \\o/""#);
+    }
+
+    #[test]
+    fn validates_attributes() {
+        let filter = Filter::new("urls.primary", Operator::Sw, "https://").unwrap();
+        assert_eq!(filter.to_string(), r#"urls.primary sw "https://""#);
+
+        for attribute in ["", "title or x", "title\"", "tag)"] {
+            assert!(
+                Filter::new(attribute, Operator::Eq, "x").is_err(),
+                "{}",
+                attribute
+            );
+        }
+    }
+
+    #[test]
+    fn groups_nested_expressions() {
+        let filter = Filter::title_eq("a")
+            .or(Filter::title_eq("b"))
+            .and(Filter::tag_eq("prod"));
+
+        assert_eq!(
+            filter.to_string(),
+            r#"(title eq "a" or title eq "b") and tag eq "prod""#
+        );
+    }
+}
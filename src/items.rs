@@ -1,11 +1,12 @@
 //! Vault items
 
-use crate::error::{ConnectAPIError, Error};
+use crate::error::CustomError;
 use crate::{
-    client::HTTPClient,
+    client::{ConnectRequest, HTTPClient},
+    filter::Filter,
     models::{
         item::{FullItem, ItemData},
-        StatusWrapper,
+        patch::ItemPatch,
     },
 };
 use serde::Deserialize;
@@ -15,69 +16,47 @@ pub async fn all(
     client: &impl HTTPClient,
     id: &str,
 ) -> Result<(Vec<ItemData>, serde_json::Value), crate::error::Error> {
-    let params = vec![("", "")];
-    let path = format!("v1/vaults/{}/items", id);
-
-    let result = match client
-        .send_request::<Vec<ItemData>>("GET", &path, &params, None)
-        .await
-    {
-        Ok(value) => value,
-        Err(err) => {
-            let op_error = crate::error::process_connect_error_response(err.to_string())?;
-
-            let message = "Invalid bearer token";
-            if err.to_string().contains(message) {
-                let status = StatusWrapper {
-                    status: op_error.status_code.unwrap_or_default(),
-                };
-
-                return Err(Error::new_connect_error(ConnectAPIError::new(
-                    status.into(),
-                    message,
-                )));
-            }
+    list(client, ConnectRequest::get(&["v1", "vaults", id, "items"])).await
+}
 
-            return Err(Error::new_internal_error().with(err));
-        }
-    };
+/// Get all items matching a filter, i.e. `Filter::title_eq("Dell XYZ")`
+pub async fn all_filtered(
+    client: &impl HTTPClient,
+    id: &str,
+    filter: &Filter,
+) -> Result<(Vec<ItemData>, serde_json::Value), crate::error::Error> {
+    let request =
+        ConnectRequest::get(&["v1", "vaults", id, "items"]).query("filter", &filter.to_string());
+
+    list(client, request).await
+}
+
+async fn list(
+    client: &impl HTTPClient,
+    request: ConnectRequest,
+) -> Result<(Vec<ItemData>, serde_json::Value), crate::error::Error> {
+    let result = client.send_request::<Vec<ItemData>>(request).await?;
 
     Ok(result)
 }
 
 /// Get item details
+///
+/// When `inline_files` is set, the contents of attached files are returned Base64-encoded in
+/// [`FileObject::content`](crate::models::file::FileObject::content); use
+/// [`crate::files::content`] instead to stream large attachments.
 pub async fn get(
     client: &impl HTTPClient,
     vault_id: &str,
     item_id: &str,
+    inline_files: bool,
 ) -> Result<(FullItem, serde_json::Value), crate::error::Error> {
-    let params = vec![("", "")];
-    let path = format!("v1/vaults/{}/items/{}", vault_id, item_id);
-
-    let body = None;
-    let result = match client
-        .send_request::<FullItem>("GET", &path, &params, body)
-        .await
-    {
-        Ok(value) => value,
-        Err(err) => {
-            let op_error = crate::error::process_connect_error_response(err.to_string())?;
-
-            let message = "Invalid bearer token";
-            if err.to_string().contains(message) {
-                let status = StatusWrapper {
-                    status: op_error.status_code.unwrap_or_default(),
-                };
-
-                return Err(Error::new_connect_error(ConnectAPIError::new(
-                    status.into(),
-                    message,
-                )));
-            }
+    let mut request = ConnectRequest::get(&["v1", "vaults", vault_id, "items", item_id]);
+    if inline_files {
+        request = request.query("inline_files", "true");
+    }
 
-            return Err(Error::new_internal_error().with(err));
-        }
-    };
+    let result = client.send_request::<FullItem>(request).await?;
 
     Ok(result)
 }
@@ -89,33 +68,46 @@ pub async fn add(
 ) -> Result<(ItemData, serde_json::Value), crate::error::Error> {
     let id = &item.vault.id;
 
-    let params = vec![("", "")];
-    let path = format!("v1/vaults/{}/items", id);
-
-    let body = Some(serde_json::to_string(&item)?);
-    let result = match client
-        .send_request::<ItemData>("POST", &path, &params, body)
-        .await
-    {
-        Ok(value) => value,
-        Err(err) => {
-            let op_error = crate::error::process_connect_error_response(err.to_string())?;
-
-            let message = "Invalid bearer token";
-            if err.to_string().contains(message) {
-                let status = StatusWrapper {
-                    status: op_error.status_code.unwrap_or_default(),
-                };
-
-                return Err(Error::new_connect_error(ConnectAPIError::new(
-                    status.into(),
-                    message,
-                )));
-            }
+    let request = ConnectRequest::post(&["v1", "vaults", id, "items"]).json(&item)?;
 
-            return Err(Error::new_internal_error().with(err));
-        }
-    };
+    let result = client.send_request::<ItemData>(request).await?;
+
+    Ok(result)
+}
+
+/// Replace an item
+///
+/// The item must carry its `id`, typically by fetching it with [`get`] first. When `version` is
+/// set and no longer matches the server, a version conflict error is returned, see
+/// [`Error::is_version_conflict`](crate::error::Error::is_version_conflict).
+pub async fn update(
+    client: &impl HTTPClient,
+    item: FullItem,
+) -> Result<(ItemData, serde_json::Value), crate::error::Error> {
+    let id = &item.vault.id;
+    let item_id = item
+        .id
+        .as_ref()
+        .ok_or_else(|| CustomError::new("Item ID is required"))?;
+
+    let request = ConnectRequest::put(&["v1", "vaults", id, "items", item_id]).json(&item)?;
+
+    let result = client.send_request::<ItemData>(request).await?;
+
+    Ok(result)
+}
+
+/// Apply a JSON Patch to an item
+pub async fn patch(
+    client: &impl HTTPClient,
+    vault_id: &str,
+    item_id: &str,
+    patch: &ItemPatch,
+) -> Result<(FullItem, serde_json::Value), crate::error::Error> {
+    let request =
+        ConnectRequest::patch(&["v1", "vaults", vault_id, "items", item_id]).json(patch)?;
+
+    let result = client.send_request::<FullItem>(request).await?;
 
     Ok(result)
 }
@@ -129,47 +121,16 @@ pub async fn remove(
     id: &str,
     item_id: &str,
 ) -> Result<(), crate::error::Error> {
-    let params = vec![("", "")];
-    let path = format!("v1/vaults/{}/items/{}", id, item_id);
-    dbg!(&path);
-
-    let body = None;
-    let _result = match client
-        .send_request::<DeleteReturnType>("DELETE", &path, &params, body)
-        .await
-    {
-        Ok(value) => value,
-        Err(err) => {
-            let op_error = crate::error::process_connect_error_response(err.to_string())?;
-
-            let message = "Invalid bearer token";
-            if err.to_string().contains(message) {
-                let status = StatusWrapper {
-                    status: op_error.status_code.unwrap_or_default(),
-                };
-
-                return Err(Error::new_connect_error(ConnectAPIError::new(
-                    status.into(),
-                    message,
-                )));
-            }
+    let request = ConnectRequest::delete(&["v1", "vaults", id, "items", item_id]);
 
-            return Err(Error::new_internal_error().with(err));
-        }
-    };
+    let _result = client.send_request::<DeleteReturnType>(request).await?;
 
     Ok(())
 }
 
-#[allow(dead_code)]
-const SLEEP_DELAY: u64 = 4; // seconds
-
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     mod default {
-        use super::SLEEP_DELAY;
         use crate::get_test_client;
         use tokio::test;
 
@@ -190,8 +151,6 @@ mod tests {
 
             assert_ne!(new_item.id, "foo");
 
-            tokio::time::sleep(std::time::Duration::new(SLEEP_DELAY, 0)).await;
-
             items::remove(&client, &test_vault_id, &new_item.id)
                 .await
                 .unwrap();
@@ -203,20 +162,22 @@ mod tests {
 
             let (items, _) = items::all(&client, &test_vault_id).await.unwrap();
 
-            if items.len() == 0 {
+            if items.is_empty() {
                 assert!(items.is_empty());
             }
         }
     }
 
     mod login_item {
-        use super::SLEEP_DELAY;
         use crate::get_test_client;
         use tokio::test;
 
         use crate::{
             items,
-            models::item::{FullItem, ItemBuilder, ItemCategory, LoginItem},
+            models::{
+                item::{FullItem, ItemBuilder, ItemCategory, LoginItem},
+                patch::ItemPatch,
+            },
         };
 
         #[test]
@@ -234,8 +195,6 @@ mod tests {
 
             assert_ne!(new_item.id, "foo");
 
-            tokio::time::sleep(std::time::Duration::new(SLEEP_DELAY, 0)).await;
-
             items::remove(&client, &test_vault_id, &new_item.id)
                 .await
                 .unwrap();
@@ -257,8 +216,6 @@ mod tests {
 
             assert_ne!(new_item.id, "foo");
 
-            tokio::time::sleep(std::time::Duration::new(SLEEP_DELAY, 0)).await;
-
             items::remove(&client, &test_vault_id, &new_item.id)
                 .await
                 .unwrap();
@@ -290,25 +247,87 @@ mod tests {
             let (new_item, _) = items::add(&client, item).await.unwrap();
             dbg!(&new_item);
 
-            tokio::time::sleep(std::time::Duration::new(SLEEP_DELAY + 2, 0)).await;
-
             items::remove(&client, &test_vault_id, &new_item.id)
                 .await
                 .unwrap();
 
-            tokio::time::sleep(std::time::Duration::new(SLEEP_DELAY, 0)).await;
-
             let (items, _) = items::all(&client, &test_vault_id).await.unwrap();
             assert!(items.is_empty());
         }
+
+        #[test]
+        async fn update_login_item() {
+            let (client, test_vault_id) = get_test_client();
+
+            let item: FullItem = ItemBuilder::new(&test_vault_id, ItemCategory::Login)
+                .title("Test login item, will be updated")
+                .username("Bob")
+                .password("")
+                .build()
+                .unwrap();
+            let (new_item, _) = items::add(&client, item).await.unwrap();
+
+            let (mut item, _) = items::get(&client, &test_vault_id, &new_item.id, false)
+                .await
+                .unwrap();
+            item.title = "Test login item, updated".to_string();
+
+            let (updated_item, _) = items::update(&client, item).await.unwrap();
+            assert_eq!(updated_item.id, new_item.id);
+            assert_eq!(updated_item.title, "Test login item, updated");
+
+            items::remove(&client, &test_vault_id, &new_item.id)
+                .await
+                .unwrap();
+        }
+
+        #[test]
+        async fn patch_login_item() {
+            let (client, test_vault_id) = get_test_client();
+
+            let item: FullItem = ItemBuilder::new(&test_vault_id, ItemCategory::Login)
+                .title("Test login item, will be patched")
+                .username("Bob")
+                .password("")
+                .build()
+                .unwrap();
+            let (new_item, _) = items::add(&client, item).await.unwrap();
+
+            let patch = ItemPatch::new()
+                .title("Test login item, patched")
+                .tags(&["patched"]);
+            let (item, _) = items::patch(&client, &test_vault_id, &new_item.id, &patch)
+                .await
+                .unwrap();
+            assert_eq!(item.title, "Test login item, patched");
+            assert_eq!(item.tags, Some(vec!["patched".to_string()]));
+
+            items::remove(&client, &test_vault_id, &new_item.id)
+                .await
+                .unwrap();
+        }
+
+        #[test]
+        async fn update_login_item_requires_id() {
+            let (client, test_vault_id) = get_test_client();
+
+            let item: FullItem = ItemBuilder::new(&test_vault_id, ItemCategory::Login)
+                .title("Test login item")
+                .username("Bob")
+                .password("")
+                .build()
+                .unwrap();
+
+            assert!(items::update(&client, item).await.is_err());
+        }
     }
 
     mod api_credential_item {
-        use super::SLEEP_DELAY;
         use crate::get_test_client;
         use tokio::test;
 
         use crate::{
+            filter::Filter,
             items,
             models::item::{ApiCredentialItem, FullItem, ItemBuilder, ItemCategory},
         };
@@ -324,9 +343,7 @@ mod tests {
             let (new_item, _) = items::add(&client, item).await.unwrap();
             assert_eq!(new_item.title, "Dell XYZ");
 
-            tokio::time::sleep(std::time::Duration::new(SLEEP_DELAY, 0)).await;
-
-            let (item, _) = items::get(&client, &test_vault_id, &new_item.id)
+            let (item, _) = items::get(&client, &test_vault_id, &new_item.id, false)
                 .await
                 .unwrap();
             let fields: Vec<_> = item
@@ -344,7 +361,6 @@ mod tests {
             assert_eq!(api_value, "lawyer-rottenborn");
 
             // Just as a clean up measure, we remove the item created in the this example
-            tokio::time::sleep(std::time::Duration::new(SLEEP_DELAY, 0)).await;
 
             items::remove(&client, &test_vault_id, &new_item.id)
                 .await
@@ -364,11 +380,136 @@ mod tests {
 
             assert_ne!(new_item.id, "foo");
 
-            tokio::time::sleep(std::time::Duration::new(SLEEP_DELAY, 0)).await;
+            items::remove(&client, &test_vault_id, &new_item.id)
+                .await
+                .unwrap();
+        }
+
+        #[test]
+        async fn all_filtered() {
+            let (client, test_vault_id) = get_test_client();
+
+            let item: FullItem = ItemBuilder::new(&test_vault_id, ItemCategory::ApiCredential)
+                .api_key("", "Filtered \"quoted\" item")
+                .build()
+                .unwrap();
+            let (new_item, _) = items::add(&client, item).await.unwrap();
+
+            let filter = Filter::title_eq("Filtered \"quoted\" item");
+            let (items, _) = items::all_filtered(&client, &test_vault_id, &filter)
+                .await
+                .unwrap();
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].id, new_item.id);
 
             items::remove(&client, &test_vault_id, &new_item.id)
                 .await
                 .unwrap();
         }
     }
+
+    mod database_item {
+        use crate::get_test_client;
+        use tokio::test;
+
+        use crate::{
+            items,
+            models::item::{DatabaseItem, FullItem, ItemBuilder, ItemCategory},
+        };
+
+        #[test]
+        async fn add_database_item() {
+            let (client, test_vault_id) = get_test_client();
+
+            let item: FullItem = ItemBuilder::new(&test_vault_id, ItemCategory::Database)
+                .title("Orders database")
+                .database_type("postgresql")
+                .hostname("db.internal")
+                .port(5432)
+                .database("orders")
+                .username("app")
+                .password("")
+                .connection_options("sslmode=require")
+                .build()
+                .unwrap();
+            let (new_item, _) = items::add(&client, item).await.unwrap();
+            let (item, _) = items::get(&client, &test_vault_id, &new_item.id, false)
+                .await
+                .unwrap();
+
+            assert_eq!(item.category, ItemCategory::Database);
+            let credentials = item.database_credentials();
+            assert_eq!(credentials.database_type.as_deref(), Some("postgresql"));
+            assert_eq!(credentials.hostname.as_deref(), Some("db.internal"));
+            assert_eq!(credentials.port, Some(5432));
+            assert_eq!(credentials.database.as_deref(), Some("orders"));
+            assert_eq!(credentials.username.as_deref(), Some("app"));
+            assert!(credentials
+                .password
+                .is_some_and(|password| !password.is_empty()));
+            assert_eq!(
+                credentials.connection_options.as_deref(),
+                Some("sslmode=require")
+            );
+        }
+
+        #[should_panic]
+        #[test]
+        async fn add_database_item_requires_title() {
+            let (_client, test_vault_id) = get_test_client();
+
+            let _item: FullItem = ItemBuilder::new(&test_vault_id, ItemCategory::Database)
+                .hostname("db.internal")
+                .build()
+                .unwrap();
+        }
+    }
+
+    mod server_item {
+        use crate::get_test_client;
+        use tokio::test;
+
+        use crate::{
+            items,
+            models::item::{FullItem, ItemBuilder, ItemCategory, ServerItem},
+        };
+
+        #[test]
+        async fn add_server_item() {
+            let (client, test_vault_id) = get_test_client();
+
+            let item: FullItem = ItemBuilder::new(&test_vault_id, ItemCategory::Server)
+                .title("Build server")
+                .url("ssh://build.internal")
+                .username("deploy")
+                .password("hunter2")
+                .password("hunter3")
+                .admin_console("https://build.internal:8443", "admin", "")
+                .build()
+                .unwrap();
+            assert_eq!(item.fields.len(), 6);
+
+            let (new_item, _) = items::add(&client, item).await.unwrap();
+            let (item, _) = items::get(&client, &test_vault_id, &new_item.id, false)
+                .await
+                .unwrap();
+
+            let credentials = item.server_credentials();
+            assert_eq!(credentials.url.as_deref(), Some("ssh://build.internal"));
+            assert_eq!(credentials.username.as_deref(), Some("deploy"));
+            assert_eq!(credentials.password.as_deref(), Some("hunter3"));
+            assert_eq!(
+                credentials.admin_console_url.as_deref(),
+                Some("https://build.internal:8443")
+            );
+            assert_eq!(credentials.admin_console_username.as_deref(), Some("admin"));
+            assert!(credentials.admin_console_password.is_some());
+            assert_eq!(
+                item.field("admin_console_url")
+                    .and_then(|field| field.section.as_ref())
+                    .map(|section| section.id.as_str()),
+                Some("admin_console")
+            );
+        }
+    }
 }
@@ -8,8 +8,15 @@
 //!
 //! # High-level features
 //!
-//! - Based on [`tokio`], [`hyper`] and [`hyper_rustls`] by default.
-//! - [`hyper`] can be replaced using the [`HTTPClient`](client::HTTPClient) interface.
+//! - Based on [`tokio`], `hyper` and `hyper_rustls` by default.
+//! - Enable the `reqwest` feature, with `reqwest-rustls` or `reqwest-native-tls`, to use
+//!   `client::ReqwestClient` instead, and disable the default `hyper-client` feature to drop
+//!   `hyper`.
+//! - Other transports can be plugged in using the [`HTTPClient`](client::HTTPClient) interface.
+//! - Enable the `testing` feature for `testing::FakeConnect`, an in-process fake Connect server to
+//!   run tests against.
+//! - Enable the `ssh` feature for `models::SshKeyItem`, to store existing or generated SSH keys.
+//! - Enable the `cli` feature for the `connect-1password` command-line binary.
 //!
 //! # Examples
 //!
@@ -18,7 +25,8 @@
 //! To create a Login item, make sure to use the Trait [`LoginItem`](models::item::LoginItem), so as to be able to call
 //! respective methods (enforced by the interface) on [`ItemBuilder`](models::item::ItemBuilder).
 //!
-//! ```
+//! ```no_run
+//! # #[cfg(feature = "hyper-client")]
 //! use connect_1password::{
 //!     error::Error,
 //!     client::{Client, HTTPClient},
@@ -29,8 +37,7 @@
 //!     items,
 //! };
 //!
-//! const SLEEP_DELAY: u64 = 4; // seconds
-//!
+//! # #[cfg(feature = "hyper-client")]
 //! #[tokio::main]
 //! async fn main() -> Result<(), Error> {
 //!     let client = Client::default();
@@ -49,13 +56,13 @@
 //!     assert_eq!(new_item.title, "Secure server login");
 //!
 //!     // Just as a clean up measure, we remove the item created in the this example
-//!     tokio::time::sleep(std::time::Duration::new(SLEEP_DELAY, 0)).await;
-//!
 //!     items::remove(&client, &vaults[0].id, &new_item.id)
-//!         .await;
+//!         .await?;
 //!
 //!     Ok(())
 //! }
+//! # #[cfg(not(feature = "hyper-client"))]
+//! # fn main() {}
 //! ```
 //!
 //! ## Create an API Credential item
@@ -66,7 +73,8 @@
 //! In the example below, since we have not provided a specific API key value, one is generated for
 //! us by the Connect API.
 //!
-//! ```
+//! ```no_run
+//! # #[cfg(feature = "hyper-client")]
 //! use connect_1password::{
 //!     error::Error,
 //!     client::{Client, HTTPClient},
@@ -77,8 +85,7 @@
 //!     items,
 //! };
 //!
-//! const SLEEP_DELAY: u64 = 4; // seconds
-//!
+//! # #[cfg(feature = "hyper-client")]
 //! #[tokio::main]
 //! async fn main() -> Result<(), Error> {
 //!     let client = Client::default();
@@ -95,9 +102,7 @@
 //!     let (new_item, _) = items::add(&client, item).await?;
 //!     assert_eq!(new_item.title, "Dell XYZ");
 //!
-//!     tokio::time::sleep(std::time::Duration::new(SLEEP_DELAY, 0)).await;
-//!
-//!     let (item, _) = items::get(&client, &vaults[0].id, &new_item.id).await?;
+//!     let (item, _) = items::get(&client, &vaults[0].id, &new_item.id, false).await?;
 //!     let fields: Vec<_> = item.fields.into_iter().filter(|r| r.value.is_some()).collect();
 //!     assert_eq!(fields.len(), 1);
 //!
@@ -108,18 +113,19 @@
 //!     assert!(!api_value.is_empty());
 //!
 //!     // Just as a clean up measure, we remove the item created in the this example
-//!     tokio::time::sleep(std::time::Duration::new(SLEEP_DELAY, 0)).await;
-//!
 //!     items::remove(&client, &vaults[0].id, &new_item.id)
 //!         .await?;
 //!
 //!     Ok(())
 //! }
+//! # #[cfg(not(feature = "hyper-client"))]
+//! # fn main() {}
 //! ```
 //!
 //! However, if we provide a specific key, this is the value persisted into 1Password.
 //!
-//! ```
+//! ```no_run
+//! # #[cfg(feature = "hyper-client")]
 //! use connect_1password::{
 //!     error::Error,
 //!     client::{Client, HTTPClient},
@@ -130,8 +136,7 @@
 //!     items,
 //! };
 //!
-//! const SLEEP_DELAY: u64 = 4; // seconds
-//!
+//! # #[cfg(feature = "hyper-client")]
 //! #[tokio::main]
 //! async fn main() -> Result<(), Error> {
 //!     let client = Client::default();
@@ -147,10 +152,8 @@
 //!     let (new_item, _) = items::add(&client, item).await?;
 //!     assert_eq!(new_item.title, "Dell XYZ");
 //!
-//!     tokio::time::sleep(std::time::Duration::new(SLEEP_DELAY, 0)).await;
-//!
 //!     let client = Client::default();
-//!     let (item, _) = items::get(&client, &vaults[0].id, &new_item.id).await?;
+//!     let (item, _) = items::get(&client, &vaults[0].id, &new_item.id, false).await?;
 //!     let fields: Vec<_> = item.fields.into_iter().filter(|r| r.value.is_some()).collect();
 //!     assert_eq!(fields.len(), 1);
 //!
@@ -161,28 +164,66 @@
 //!     assert_eq!(api_value, "smelly-socks");
 //!
 //!     // Just as a clean up measure, we remove the item created in the this example
-//!     tokio::time::sleep(std::time::Duration::new(SLEEP_DELAY, 0)).await;
-//!
 //!     items::remove(&client, &vaults[0].id, &new_item.id)
 //!         .await?;
 //!
 //!     Ok(())
 //! }
+//! # #[cfg(not(feature = "hyper-client"))]
+//! # fn main() {}
 //! ```
 
+pub mod activity;
+pub mod blocking;
 pub mod client;
 pub mod error;
+pub mod files;
+pub mod filter;
 pub mod items;
 pub mod models;
+pub mod secrets;
+pub mod server;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod vaults;
 
+#[cfg(all(test, not(any(feature = "hyper-client", feature = "reqwest"))))]
+compile_error!("the tests need the `hyper-client` or `reqwest` feature");
+
 #[cfg(test)]
-fn get_test_client() -> (client::Client, String) {
-    use dotenv::dotenv;
-    dotenv().ok();
+fn get_test_client() -> (impl client::HTTPClient, String) {
+    let server = testing::FakeConnect::start();
+    let test_vault_id = server.add_vault("connect-1password");
 
-    let test_vault_id =
-        std::env::var("OP_TESTING_VAULT_ID").expect("1Password Vault ID for testing");
+    #[cfg(feature = "hyper-client")]
+    let inner = server.client();
+    #[cfg(not(feature = "hyper-client"))]
+    let inner = server.reqwest_client();
+
+    (
+        TestClient {
+            inner,
+            _server: server,
+        },
+        test_vault_id,
+    )
+}
 
-    (client::Client::default(), test_vault_id)
+/// Keeps the fake server of a test running for as long as its client is in use.
+#[cfg(test)]
+#[derive(Debug)]
+struct TestClient<C> {
+    inner: C,
+    _server: testing::FakeConnect,
+}
+
+#[cfg(test)]
+#[async_trait::async_trait]
+impl<C: client::HTTPClient> client::HTTPClient for TestClient<C> {
+    async fn execute(
+        &self,
+        request: client::ConnectRequest,
+    ) -> Result<client::ConnectResponse, error::Error> {
+        self.inner.execute(request).await
+    }
 }
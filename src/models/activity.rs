@@ -0,0 +1,95 @@
+use crate::models::item::{ItemID, VaultID};
+use chrono::{DateTime, Utc};
+
+use serde::Deserialize;
+
+/// Defines an API request made to the Connect server
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiRequest {
+    /// The UUID of the request.
+    pub request_id: String,
+    /// Date and time when the request was made.
+    pub timestamp: DateTime<Utc>,
+    /// The action the request performed.
+    pub action: ApiRequestAction,
+    /// Whether the request was allowed.
+    pub result: ApiRequestResult,
+    /// The token that made the request.
+    pub actor: ApiRequestActor,
+    /// The vault or item the request acted upon.
+    pub resource: Option<ApiRequestResource>,
+}
+
+/// Describes the action of an API request
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ApiRequestAction {
+    /// A vault or item was read
+    Read,
+    /// An item was created
+    Create,
+    /// An item was updated
+    Update,
+    /// An item was deleted
+    Delete,
+    /// An action not known to this SDK
+    #[serde(other)]
+    Unknown,
+}
+
+/// Describes the outcome of an API request
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ApiRequestResult {
+    /// The request was allowed
+    Success,
+    /// The request was denied
+    Deny,
+    /// A result not known to this SDK
+    #[serde(other)]
+    Unknown,
+}
+
+/// Defines the token which made an API request
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiRequestActor {
+    /// The UUID of the token.
+    pub id: String,
+    /// The UUID of the 1Password account.
+    pub account: String,
+    /// The JWT ID of the token.
+    pub jti: String,
+    /// The user agent of the client.
+    pub user_agent: Option<String>,
+    /// The IP address of the client.
+    pub request_ip: Option<String>,
+}
+
+/// Defines the resource an API request acted upon
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiRequestResource {
+    /// The type of the resource.
+    pub r#type: ApiRequestResourceType,
+    /// An object containing the UUID of the vault.
+    pub vault: Option<VaultID>,
+    /// An object containing the UUID of the item, if the resource is an item.
+    pub item: Option<ItemID>,
+    /// The version of the item at the time of the request.
+    pub item_version: Option<u32>,
+}
+
+/// Describes the type of resource an API request acted upon
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ApiRequestResourceType {
+    /// A vault
+    Vault,
+    /// An item
+    Item,
+    /// A resource not known to this SDK
+    #[serde(other)]
+    Unknown,
+}
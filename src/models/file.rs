@@ -0,0 +1,33 @@
+use crate::{error::Error, models::item::SectionID};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+
+/// Defines a File Object attached to an item
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct FileObject {
+    /// The UUID of the file.
+    pub id: String,
+    /// The name of the file.
+    pub name: String,
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// The path to download the contents of the file.
+    pub content_path: String,
+    /// The Base64-encoded contents of the file, only present when fetched with `inline_files`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// An object containing the UUID of a section in the item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<SectionID>,
+}
+
+impl FileObject {
+    /// Decode the inline contents of the file, returns `None` unless the file was fetched with
+    /// `inline_files` enabled.
+    pub fn decoded_content(&self) -> Option<Result<Vec<u8>, Error>> {
+        self.content
+            .as_ref()
+            .map(|content| STANDARD.decode(content).map_err(Error::new_parsing_error))
+    }
+}
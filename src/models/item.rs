@@ -1,7 +1,9 @@
 use crate::error::{CustomError, ErrorTrait};
+use crate::models::file::FileObject;
 use chrono::{DateTime, Utc};
 
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use uuid::Uuid;
 
 /// Defines an Item Object
@@ -14,7 +16,8 @@ pub struct ItemData {
     /// An object containing an id property whose value is the UUID of the vault the item is in.
     pub vault: VaultID,
     /// The category of the item.
-    pub category: Option<String>,
+    #[serde(default)]
+    pub category: ItemCategory,
     /// Vector of URL objects containing URLs for the item.
     pub urls: Option<Vec<UrlObject>>,
     /// Whether the item is marked as a favourite.
@@ -36,6 +39,13 @@ pub struct VaultID {
     pub id: String,
 }
 
+/// Defines the Item UUID via a record struct
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct ItemID {
+    /// The UUID of the item.
+    pub id: String,
+}
+
 /// Defines a URL Object
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 pub struct UrlObject {
@@ -46,8 +56,11 @@ pub struct UrlObject {
 }
 
 /// This is a Field Object
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct FieldObject {
+    /// The ID of the field, assigned by Connect.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     /// An object containing the UUID of a section in the item.
     pub section: Option<SectionID>,
     /// Use `purpose` for the username, password, and notes fields.
@@ -62,6 +75,28 @@ pub struct FieldObject {
     // pub recipe
     /// Some optional text
     pub label: Option<String>,
+    /// The current one-time password, only present on `OTP` fields returned by Connect.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub totp: Option<String>,
+}
+
+/// Field types and purposes whose values are secrets, see [`FieldObject::is_secret`].
+pub const SECRET_FIELDS: &[&str] = &[
+    "CONCEALED",
+    "PASSWORD",
+    "OTP",
+    "CREDIT_CARD_NUMBER",
+    "SSHKEY",
+];
+
+impl FieldObject {
+    /// Returns true if the value of this field is a secret, i.e. a password or one-time password.
+    pub fn is_secret(&self) -> bool {
+        [&self.r#type, &self.purpose]
+            .into_iter()
+            .flatten()
+            .any(|kind| SECRET_FIELDS.contains(&kind.as_str()))
+    }
 }
 
 /// Used to specify type of a Field Object
@@ -101,7 +136,7 @@ impl SectionObject {
 }
 
 /// This is a SectionID
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct SectionID {
     /// The UUID of the section.
     pub id: String,
@@ -125,12 +160,19 @@ impl Default for SectionID {
 /// This is a FullItem
 #[derive(Debug, Deserialize, Serialize)]
 pub struct FullItem {
+    /// The UUID of the item, this is required when replacing an existing item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The version of the item, used by Connect to detect conflicting updates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
     /// The title of the item.
     pub title: String,
     /// An object containing an id property whose value is the UUID of the vault the item is in.
     pub vault: VaultID,
     /// The category of the item.
-    pub category: Option<String>,
+    #[serde(default)]
+    pub category: ItemCategory,
     /// Vector of URL objects containing URLs for the item.
     pub urls: Option<Vec<UrlObject>>,
     /// Whether the item is marked as a favourite.
@@ -138,9 +180,14 @@ pub struct FullItem {
     /// A vector of strings of the tags assigned to the item.
     pub tags: Option<Vec<String>>,
     /// A vector of Field objects of the fields to include with the item.
+    #[serde(default)]
     pub fields: Vec<FieldObject>,
     /// A vector of Section objects of the sections to include with the item.
+    #[serde(default)]
     pub sections: Vec<SectionObject>,
+    /// A vector of File objects of the files attached to the item, never sent back to Connect.
+    #[serde(skip_serializing)]
+    pub files: Option<Vec<FileObject>>,
 }
 
 /// Defines a default interface
@@ -169,6 +216,162 @@ pub trait ApiCredentialItem {
     fn build(&self) -> Result<FullItem, Box<dyn std::error::Error + Send + Sync>>;
 }
 
+/// Defines an interface for a Database item
+///
+/// Fields use the IDs of 1Password's Database template, i.e. `hostname` and `database_type`.
+pub trait DatabaseItem {
+    /// Specify title
+    fn title(self, title: &str) -> Self;
+    /// Specify the type of database, i.e. `postgresql` or `mysql`
+    fn database_type(self, database_type: &str) -> Self;
+    /// Specify hostname
+    fn hostname(self, hostname: &str) -> Self;
+    /// Specify port
+    fn port(self, port: u16) -> Self;
+    /// Specify the name of the database
+    fn database(self, database: &str) -> Self;
+    /// Specify username
+    fn username(self, username: &str) -> Self;
+    /// Specify password, one is generated when empty
+    fn password(self, password: &str) -> Self;
+    /// Specify connection options, i.e. `sslmode=require`
+    fn connection_options(self, options: &str) -> Self;
+    /// Execute the builder
+    fn build(&self) -> Result<FullItem, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// Defines an interface for a Server item
+///
+/// Fields use the IDs of 1Password's Server template, with the admin console in its
+/// `admin_console` section.
+pub trait ServerItem {
+    /// Specify title
+    fn title(self, title: &str) -> Self;
+    /// Specify the URL of the server
+    fn url(self, url: &str) -> Self;
+    /// Specify username
+    fn username(self, username: &str) -> Self;
+    /// Specify password, one is generated when empty
+    fn password(self, password: &str) -> Self;
+    /// Specify the URL, username and password of the admin console
+    fn admin_console(self, url: &str, username: &str, password: &str) -> Self;
+    /// Execute the builder
+    fn build(&self) -> Result<FullItem, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// Defines the fields of a Database item, see [`FullItem::database_credentials`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatabaseCredentials {
+    /// The type of database.
+    pub database_type: Option<String>,
+    /// The hostname of the server.
+    pub hostname: Option<String>,
+    /// The port, if set to a valid port number.
+    pub port: Option<u16>,
+    /// The name of the database.
+    pub database: Option<String>,
+    /// The username.
+    pub username: Option<String>,
+    /// The password.
+    pub password: Option<String>,
+    /// The connection options.
+    pub connection_options: Option<String>,
+}
+
+/// Defines the fields of a Server item, see [`FullItem::server_credentials`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerCredentials {
+    /// The URL of the server.
+    pub url: Option<String>,
+    /// The username.
+    pub username: Option<String>,
+    /// The password.
+    pub password: Option<String>,
+    /// The URL of the admin console.
+    pub admin_console_url: Option<String>,
+    /// The username of the admin console.
+    pub admin_console_username: Option<String>,
+    /// The password of the admin console.
+    pub admin_console_password: Option<String>,
+}
+
+impl FullItem {
+    /// Returns the field with the given ID.
+    pub fn field(&self, id: &str) -> Option<&FieldObject> {
+        self.fields
+            .iter()
+            .find(|field| field.id.as_deref() == Some(id))
+    }
+
+    /// Returns the value of the field with the given ID, if it is set.
+    pub fn field_value(&self, id: &str) -> Option<&str> {
+        self.field(id).and_then(|field| field.value.as_deref())
+    }
+
+    /// Read the fields written by [`DatabaseItem`], missing fields are `None`.
+    pub fn database_credentials(&self) -> DatabaseCredentials {
+        let value = |id| self.field_value(id).map(str::to_string);
+
+        DatabaseCredentials {
+            database_type: value(DATABASE_TYPE),
+            hostname: value(HOSTNAME),
+            port: self.field_value(PORT).and_then(|port| port.parse().ok()),
+            database: value(DATABASE),
+            username: value(USERNAME),
+            password: value(PASSWORD),
+            connection_options: value(CONNECTION_OPTIONS),
+        }
+    }
+
+    /// Read the fields written by [`ServerItem`], missing fields are `None`.
+    pub fn server_credentials(&self) -> ServerCredentials {
+        let value = |id| self.field_value(id).map(str::to_string);
+
+        ServerCredentials {
+            url: value(URL),
+            username: value(USERNAME),
+            password: value(PASSWORD),
+            admin_console_url: value(ADMIN_CONSOLE_URL),
+            admin_console_username: value(ADMIN_CONSOLE_USERNAME),
+            admin_console_password: value(ADMIN_CONSOLE_PASSWORD),
+        }
+    }
+}
+
+// Field IDs of 1Password's Database and Server templates
+const DATABASE_TYPE: &str = "database_type";
+const HOSTNAME: &str = "hostname";
+const PORT: &str = "port";
+const DATABASE: &str = "database";
+const USERNAME: &str = "username";
+const PASSWORD: &str = "password";
+const CONNECTION_OPTIONS: &str = "options";
+const URL: &str = "url";
+const ADMIN_CONSOLE: &str = "admin_console";
+const ADMIN_CONSOLE_URL: &str = "admin_console_url";
+const ADMIN_CONSOLE_USERNAME: &str = "admin_console_username";
+const ADMIN_CONSOLE_PASSWORD: &str = "admin_console_password";
+
+/// A field of a template, with its well-known ID
+pub(super) fn template_field(id: &str, label: &str, r#type: &str, value: &str) -> FieldObject {
+    FieldObject {
+        id: Some(id.to_string()),
+        label: Some(label.to_string()),
+        r#type: Some(r#type.to_string()),
+        value: Some(value.to_string()),
+        ..Default::default()
+    }
+}
+
+/// A concealed field of a template, which is generated when `value` is empty
+fn template_secret(id: &str, label: &str, value: &str) -> FieldObject {
+    FieldObject {
+        value: (!value.is_empty()).then(|| value.to_string()),
+        generate: value.is_empty().then_some(true),
+        ..template_field(id, label, "CONCEALED", value)
+    }
+}
+
 /// This is an ItemBuilder
 #[derive(Debug)]
 pub struct ItemBuilder {
@@ -177,7 +380,7 @@ pub struct ItemBuilder {
     /// An object containing an id property whose value is the UUID of the vault the item is in.
     pub vault: VaultID,
     /// The category of the item.
-    pub category: Option<String>,
+    pub category: ItemCategory,
     /// Vector of URL objects containing URLs for the item.
     pub urls: Option<Vec<UrlObject>>,
     /// Whether the item is marked as a favourite.
@@ -188,35 +391,147 @@ pub struct ItemBuilder {
     pub fields: Vec<FieldObject>,
     /// A vector of Section objects of the sections to include with the item.
     pub sections: Vec<SectionObject>,
+    /// A failure of an earlier builder method, returned by `build`.
+    pub(super) error: Option<String>,
 }
 
 /// Describes usable Item categories
-#[derive(Debug)]
+///
+/// Categories are (de)serialized as Connect's `SCREAMING_SNAKE_CASE` names, i.e. `SECURE_NOTE`;
+/// categories not known to this SDK are kept as [`ItemCategory::Unknown`]. Items without a
+/// category are [`ItemCategory::Custom`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
 pub enum ItemCategory {
     /// API Credential
     ApiCredential,
+    /// Bank Account
+    BankAccount,
+    /// Credit Card
+    CreditCard,
+    /// Custom
+    #[default]
+    Custom,
+    /// Database
+    Database,
+    /// Document
+    Document,
+    /// Driver License
+    DriverLicense,
+    /// Email Account
+    Email,
+    /// Identity
+    Identity,
     /// Login
     Login,
+    /// Medical Record
+    MedicalRecord,
+    /// Membership
+    Membership,
+    /// Outdoor License
+    OutdoorLicense,
+    /// Passport
+    Passport,
     /// Password
     Password,
+    /// Reward Program
+    RewardProgram,
+    /// Secure Note
+    SecureNote,
+    /// Server
+    Server,
+    /// Social Security Number
+    SocialSecurityNumber,
+    /// Software License
+    SoftwareLicense,
+    /// SSH Key
+    SshKey,
+    /// Wireless Router
+    WirelessRouter,
+    /// A category not known to this SDK, holding its name
+    Unknown(String),
 }
 
 impl ItemCategory {
-    #[allow(dead_code)]
-    fn default() -> Self {
-        Self::ApiCredential
+    /// The name Connect uses for this category, i.e. `SECURE_NOTE`
+    pub fn as_str(&self) -> &str {
+        match self {
+            ItemCategory::ApiCredential => "API_CREDENTIAL",
+            ItemCategory::BankAccount => "BANK_ACCOUNT",
+            ItemCategory::CreditCard => "CREDIT_CARD",
+            ItemCategory::Custom => "CUSTOM",
+            ItemCategory::Database => "DATABASE",
+            ItemCategory::Document => "DOCUMENT",
+            ItemCategory::DriverLicense => "DRIVER_LICENSE",
+            ItemCategory::Email => "EMAIL_ACCOUNT",
+            ItemCategory::Identity => "IDENTITY",
+            ItemCategory::Login => "LOGIN",
+            ItemCategory::MedicalRecord => "MEDICAL_RECORD",
+            ItemCategory::Membership => "MEMBERSHIP",
+            ItemCategory::OutdoorLicense => "OUTDOOR_LICENSE",
+            ItemCategory::Passport => "PASSPORT",
+            ItemCategory::Password => "PASSWORD",
+            ItemCategory::RewardProgram => "REWARD_PROGRAM",
+            ItemCategory::SecureNote => "SECURE_NOTE",
+            ItemCategory::Server => "SERVER",
+            ItemCategory::SocialSecurityNumber => "SOCIAL_SECURITY_NUMBER",
+            ItemCategory::SoftwareLicense => "SOFTWARE_LICENSE",
+            ItemCategory::SshKey => "SSH_KEY",
+            ItemCategory::WirelessRouter => "WIRELESS_ROUTER",
+            ItemCategory::Unknown(category) => category,
+        }
+    }
+}
+
+/// Known names are matched regardless of case, so that [`ItemCategory::Unknown`] never holds one.
+impl From<&str> for ItemCategory {
+    fn from(value: &str) -> Self {
+        match value.to_ascii_uppercase().as_str() {
+            "API_CREDENTIAL" => ItemCategory::ApiCredential,
+            "BANK_ACCOUNT" => ItemCategory::BankAccount,
+            "CREDIT_CARD" => ItemCategory::CreditCard,
+            "CUSTOM" => ItemCategory::Custom,
+            "DATABASE" => ItemCategory::Database,
+            "DOCUMENT" => ItemCategory::Document,
+            "DRIVER_LICENSE" => ItemCategory::DriverLicense,
+            "EMAIL_ACCOUNT" => ItemCategory::Email,
+            "IDENTITY" => ItemCategory::Identity,
+            "LOGIN" => ItemCategory::Login,
+            "MEDICAL_RECORD" => ItemCategory::MedicalRecord,
+            "MEMBERSHIP" => ItemCategory::Membership,
+            "OUTDOOR_LICENSE" => ItemCategory::OutdoorLicense,
+            "PASSPORT" => ItemCategory::Passport,
+            "PASSWORD" => ItemCategory::Password,
+            "REWARD_PROGRAM" => ItemCategory::RewardProgram,
+            "SECURE_NOTE" => ItemCategory::SecureNote,
+            "SERVER" => ItemCategory::Server,
+            "SOCIAL_SECURITY_NUMBER" => ItemCategory::SocialSecurityNumber,
+            "SOFTWARE_LICENSE" => ItemCategory::SoftwareLicense,
+            "SSH_KEY" => ItemCategory::SshKey,
+            "WIRELESS_ROUTER" => ItemCategory::WirelessRouter,
+            _ => ItemCategory::Unknown(value.to_string()),
+        }
+    }
+}
+
+impl From<String> for ItemCategory {
+    fn from(value: String) -> Self {
+        ItemCategory::from(value.as_str())
     }
 }
 
 impl From<ItemCategory> for String {
     fn from(val: ItemCategory) -> Self {
-        let value = match val {
-            ItemCategory::ApiCredential => "API_CREDENTIAL",
-            ItemCategory::Login => "LOGIN",
-            ItemCategory::Password => "PASSWORD",
-        };
+        match val {
+            ItemCategory::Unknown(category) => category,
+            category => category.as_str().to_string(),
+        }
+    }
+}
 
-        value.to_string()
+impl fmt::Display for ItemCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
@@ -230,12 +545,13 @@ impl ItemBuilder {
         Self {
             vault,
             title: String::default(),
-            category: Some(category.into()),
+            category,
             favorite: Some(false),
             urls: None,
             tags: None,
             fields: vec![],
             sections: vec![],
+            error: None,
         }
     }
 
@@ -254,16 +570,57 @@ impl ItemBuilder {
             r#type: Some("OTP".to_string()),
             generate: Some(true),
             value: Some(secret.to_string()),
+            ..Default::default()
         };
         self.fields.push(field_object);
 
         self
     }
+
+    /// Add a field, replacing any field with the same ID
+    pub(super) fn set_field(mut self, field: FieldObject) -> Self {
+        match self
+            .fields
+            .iter_mut()
+            .find(|existing| existing.id.is_some() && existing.id == field.id)
+        {
+            Some(existing) => *existing = field,
+            None => self.fields.push(field),
+        }
+        self
+    }
+
+    pub(super) fn build_titled(
+        &self,
+    ) -> Result<FullItem, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(error) = &self.error {
+            return Err(Box::new(CustomError::new(error)));
+        }
+        if self.title.is_empty() {
+            return Err(Box::new(CustomError::new("Title is required")));
+        }
+
+        Ok(FullItem {
+            id: None,
+            version: None,
+            title: self.title.clone(),
+            category: self.category.clone(),
+            favorite: self.favorite,
+            fields: self.fields.clone(),
+            sections: self.sections.clone(),
+            tags: self.tags.clone(),
+            urls: self.urls.clone(),
+            vault: self.vault.clone(),
+            files: None,
+        })
+    }
 }
 
 impl DefaultItem for ItemBuilder {
     fn build(&self) -> Result<FullItem, Box<dyn ErrorTrait + Send + Sync>> {
         Ok(FullItem {
+            id: None,
+            version: None,
             title: self.title.clone(),
             category: self.category.clone(),
             favorite: self.favorite,
@@ -272,6 +629,7 @@ impl DefaultItem for ItemBuilder {
             tags: self.tags.clone(),
             urls: self.urls.clone(),
             vault: self.vault.clone(),
+            files: None,
         })
     }
 }
@@ -290,6 +648,7 @@ impl LoginItem for ItemBuilder {
             label: None,
             r#type: None,
             section: None,
+            ..Default::default()
         };
 
         self.fields.push(field);
@@ -304,6 +663,7 @@ impl LoginItem for ItemBuilder {
             label: None,
             r#type: None,
             section: None,
+            ..Default::default()
         };
 
         self.fields.push(field);
@@ -316,6 +676,8 @@ impl LoginItem for ItemBuilder {
         }
 
         Ok(FullItem {
+            id: None,
+            version: None,
             title: self.title.clone(),
             category: self.category.clone(),
             favorite: self.favorite,
@@ -324,6 +686,7 @@ impl LoginItem for ItemBuilder {
             tags: self.tags.clone(),
             urls: self.urls.clone(),
             vault: self.vault.clone(),
+            files: None,
         })
     }
 }
@@ -342,6 +705,7 @@ impl ApiCredentialItem for ItemBuilder {
             r#type: Some(FieldType::Concealed.into()),
             generate: Some(key.is_empty()),
             value: Some(key.to_string()),
+            ..Default::default()
         };
         self.fields.push(field_object);
         self.title = title.to_string();
@@ -351,6 +715,8 @@ impl ApiCredentialItem for ItemBuilder {
 
     fn build(&self) -> Result<FullItem, Box<dyn std::error::Error + Send + Sync>> {
         Ok(FullItem {
+            id: None,
+            version: None,
             title: self.title.clone(),
             category: self.category.clone(),
             favorite: self.favorite,
@@ -359,6 +725,195 @@ impl ApiCredentialItem for ItemBuilder {
             tags: self.tags.clone(),
             urls: self.urls.clone(),
             vault: self.vault.clone(),
+            files: None,
         })
     }
 }
+
+impl DatabaseItem for ItemBuilder {
+    fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    fn database_type(self, database_type: &str) -> Self {
+        self.set_field(template_field(DATABASE_TYPE, "type", "MENU", database_type))
+    }
+
+    fn hostname(self, hostname: &str) -> Self {
+        self.set_field(template_field(HOSTNAME, "server", "STRING", hostname))
+    }
+
+    fn port(self, port: u16) -> Self {
+        self.set_field(template_field(PORT, "port", "STRING", &port.to_string()))
+    }
+
+    fn database(self, database: &str) -> Self {
+        self.set_field(template_field(DATABASE, "database", "STRING", database))
+    }
+
+    fn username(self, username: &str) -> Self {
+        self.set_field(template_field(USERNAME, "username", "STRING", username))
+    }
+
+    fn password(self, password: &str) -> Self {
+        self.set_field(template_secret(PASSWORD, "password", password))
+    }
+
+    fn connection_options(self, options: &str) -> Self {
+        self.set_field(template_field(
+            CONNECTION_OPTIONS,
+            "connection options",
+            "STRING",
+            options,
+        ))
+    }
+
+    fn build(&self) -> Result<FullItem, Box<dyn std::error::Error + Send + Sync>> {
+        self.build_titled()
+    }
+}
+
+impl ServerItem for ItemBuilder {
+    fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    fn url(self, url: &str) -> Self {
+        self.set_field(template_field(URL, "URL", "STRING", url))
+    }
+
+    fn username(self, username: &str) -> Self {
+        self.set_field(template_field(USERNAME, "username", "STRING", username))
+    }
+
+    fn password(self, password: &str) -> Self {
+        self.set_field(template_secret(PASSWORD, "password", password))
+    }
+
+    fn admin_console(mut self, url: &str, username: &str, password: &str) -> Self {
+        if !self
+            .sections
+            .iter()
+            .any(|section| section.id == ADMIN_CONSOLE)
+        {
+            self.sections
+                .push(SectionObject::new(ADMIN_CONSOLE, "Admin Console"));
+        }
+
+        let section = || {
+            Some(SectionID {
+                id: ADMIN_CONSOLE.to_string(),
+            })
+        };
+
+        self.set_field(FieldObject {
+            section: section(),
+            ..template_field(ADMIN_CONSOLE_URL, "admin console URL", "STRING", url)
+        })
+        .set_field(FieldObject {
+            section: section(),
+            ..template_field(
+                ADMIN_CONSOLE_USERNAME,
+                "admin console username",
+                "STRING",
+                username,
+            )
+        })
+        .set_field(FieldObject {
+            section: section(),
+            ..template_secret(ADMIN_CONSOLE_PASSWORD, "console password", password)
+        })
+    }
+
+    fn build(&self) -> Result<FullItem, Box<dyn std::error::Error + Send + Sync>> {
+        self.build_titled()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldObject, FullItem, ItemCategory};
+    use serde_json::json;
+
+    #[test]
+    fn categories_round_trip() {
+        for name in [
+            "LOGIN",
+            "SECURE_NOTE",
+            "EMAIL_ACCOUNT",
+            "SSH_KEY",
+            "CRYPTO_WALLET",
+        ] {
+            let category: ItemCategory = serde_json::from_value(json!(name)).unwrap();
+
+            assert_eq!(serde_json::to_value(&category).unwrap(), json!(name));
+        }
+
+        assert_eq!(ItemCategory::from("SSH_KEY"), ItemCategory::SshKey);
+        assert_eq!(ItemCategory::from("ssh_key"), ItemCategory::SshKey);
+        assert_eq!(
+            ItemCategory::from("CRYPTO_WALLET"),
+            ItemCategory::Unknown("CRYPTO_WALLET".to_string())
+        );
+    }
+
+    #[test]
+    fn detects_secret_fields() {
+        let field = |r#type: &str, purpose: Option<&str>| FieldObject {
+            r#type: Some(r#type.to_string()),
+            purpose: purpose.map(str::to_string),
+            ..Default::default()
+        };
+
+        for r#type in ["CONCEALED", "OTP", "SSHKEY", "CREDIT_CARD_NUMBER"] {
+            assert!(field(r#type, None).is_secret(), "{}", r#type);
+        }
+        assert!(field("STRING", Some("PASSWORD")).is_secret());
+        assert!(!field("STRING", Some("USERNAME")).is_secret());
+        assert!(!field("URL", None).is_secret());
+    }
+
+    #[test]
+    fn items_have_typed_categories() {
+        let item: FullItem = serde_json::from_value(json!({
+            "title": "Note",
+            "vault": { "id": "vault" },
+            "category": "SECURE_NOTE",
+        }))
+        .unwrap();
+
+        assert_eq!(item.category, ItemCategory::SecureNote);
+
+        let item: FullItem = serde_json::from_value(json!({
+            "title": "Note",
+            "vault": { "id": "vault" },
+        }))
+        .unwrap();
+
+        assert_eq!(item.category, ItemCategory::Custom);
+    }
+
+    #[test]
+    fn files_are_not_sent_back() {
+        let item: FullItem = serde_json::from_value(json!({
+            "title": "Item",
+            "vault": { "id": "vault" },
+            "category": "LOGIN",
+            "files": [{
+                "id": "file",
+                "name": "notes.txt",
+                "size": 5,
+                "content_path": "/v1/vaults/vault/items/item/files/file/content",
+                "content": "aGVsbG8=",
+            }],
+        }))
+        .unwrap();
+        assert!(item.files.is_some());
+
+        let value = serde_json::to_value(&item).unwrap();
+
+        assert!(value.get("files").is_none());
+    }
+}
@@ -1,21 +1,26 @@
 //! Models
 
+/// API activity related models
+pub mod activity;
+/// File related models
+pub mod file;
 /// Item related models
 pub mod item;
+/// Item patch related models
+pub mod patch;
+/// Server status related models
+pub mod server;
+/// SSH key item related models
+#[cfg(feature = "ssh")]
+pub mod ssh_key;
 /// Vault related models
 pub mod vault;
 
+pub use activity::*;
+pub use file::*;
 pub use item::*;
+pub use patch::*;
+pub use server::*;
+#[cfg(feature = "ssh")]
+pub use ssh_key::*;
 pub use vault::*;
-
-/// This is a wrapper to assist creating instances of `ConnectAPIError`
-#[derive(Debug)]
-pub struct StatusWrapper {
-    pub(crate) status: u16,
-}
-
-impl From<StatusWrapper> for String {
-    fn from(val: StatusWrapper) -> Self {
-        val.status.to_string()
-    }
-}
@@ -0,0 +1,169 @@
+use crate::{error::Error, models::item::FieldObject};
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Describes the RFC 6902 operations supported by Connect
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PatchOperation {
+    /// Add a value at the target location
+    Add,
+    /// Remove the value at the target location
+    Remove,
+    /// Replace the value at the target location
+    Replace,
+}
+
+/// Defines a single JSON Patch operation
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct PatchObject {
+    /// The operation to perform.
+    pub op: PatchOperation,
+    /// A JSON Pointer to the target location, i.e. `/fields/{id}/value`.
+    pub path: String,
+    /// The value to add or replace with, this is omitted for `remove`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+}
+
+/// This is an ItemPatch, a list of operations applied to an item in order.
+///
+/// ```
+/// use connect_1password::models::patch::ItemPatch;
+///
+/// let patch = ItemPatch::new()
+///     .title("Rotated credentials")
+///     .field_value("password", "hunter2")
+///     .tags(&["prod"]);
+///
+/// assert_eq!(patch.operations().len(), 3);
+/// ```
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct ItemPatch {
+    operations: Vec<PatchObject>,
+}
+
+impl ItemPatch {
+    /// Create a new, empty instance
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `value` at `path`
+    pub fn add(self, path: &str, value: Value) -> Self {
+        self.push(PatchOperation::Add, path.to_string(), Some(value))
+    }
+
+    /// Replace the value at `path` with `value`
+    pub fn replace(self, path: &str, value: Value) -> Self {
+        self.push(PatchOperation::Replace, path.to_string(), Some(value))
+    }
+
+    /// Remove the value at `path`
+    pub fn remove(self, path: &str) -> Self {
+        self.push(PatchOperation::Remove, path.to_string(), None)
+    }
+
+    /// Replace the title of the item
+    pub fn title(self, title: &str) -> Self {
+        self.replace("/title", Value::from(title))
+    }
+
+    /// Replace the tags of the item
+    pub fn tags(self, tags: &[&str]) -> Self {
+        self.replace("/tags", Value::from(tags.to_vec()))
+    }
+
+    /// Mark or unmark the item as a favourite
+    pub fn favorite(self, favorite: bool) -> Self {
+        self.replace("/favorite", Value::from(favorite))
+    }
+
+    /// Replace the value of the field identified by `field_id`
+    pub fn field_value(self, field_id: &str, value: &str) -> Self {
+        let path = format!("/fields/{}/value", escape_pointer_segment(field_id));
+        self.push(PatchOperation::Replace, path, Some(Value::from(value)))
+    }
+
+    /// Append a new field to the item, fails if the field cannot be serialized
+    pub fn add_field(self, field: &FieldObject) -> Result<Self, Error> {
+        let value = serde_json::to_value(field)?;
+
+        Ok(self.add("/fields/-", value))
+    }
+
+    /// Remove the field identified by `field_id`
+    pub fn remove_field(self, field_id: &str) -> Self {
+        let path = format!("/fields/{}", escape_pointer_segment(field_id));
+        self.push(PatchOperation::Remove, path, None)
+    }
+
+    /// Returns the operations of this patch, in the order they will be applied.
+    pub fn operations(&self) -> &[PatchObject] {
+        &self.operations
+    }
+
+    /// Returns true if the patch contains no operations.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    fn push(mut self, op: PatchOperation, path: String, value: Option<Value>) -> Self {
+        self.operations.push(PatchObject { op, path, value });
+        self
+    }
+}
+
+/// Escape a single JSON Pointer segment as per RFC 6901.
+fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ItemPatch;
+    use crate::models::item::FieldObject;
+    use serde_json::json;
+
+    #[test]
+    fn serializes_as_json_patch_document() {
+        let patch = ItemPatch::new()
+            .title("New title")
+            .field_value("password", "secret")
+            .remove_field("otp");
+
+        assert_eq!(
+            serde_json::to_value(&patch).unwrap(),
+            json!([
+                {"op": "replace", "path": "/title", "value": "New title"},
+                {"op": "replace", "path": "/fields/password/value", "value": "secret"},
+                {"op": "remove", "path": "/fields/otp"},
+            ])
+        );
+    }
+
+    #[test]
+    fn escapes_field_ids() {
+        let patch = ItemPatch::new().field_value("a/b~c", "value");
+
+        assert_eq!(patch.operations()[0].path, "/fields/a~1b~0c/value");
+    }
+
+    #[test]
+    fn appends_fields() {
+        let field = FieldObject {
+            label: Some("pin".to_string()),
+            value: Some("1234".to_string()),
+            ..Default::default()
+        };
+        let patch = ItemPatch::new().add_field(&field).unwrap();
+
+        assert_eq!(patch.operations()[0].path, "/fields/-");
+        assert_eq!(
+            patch.operations()[0].value.as_ref().unwrap()["label"],
+            "pin"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+/// Defines the health of a Connect server and its dependencies
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct ServerHealth {
+    /// The name of the server.
+    pub name: String,
+    /// The version of the server.
+    pub version: String,
+    /// The state of each service the server relies on.
+    #[serde(default)]
+    pub dependencies: Vec<ServiceDependency>,
+}
+
+impl ServerHealth {
+    /// Returns the state of the sync component, which keeps the local vault data up to date.
+    pub fn sync(&self) -> Option<&ServiceDependency> {
+        self.dependencies
+            .iter()
+            .find(|dependency| dependency.service == "sync")
+    }
+
+    /// Returns true if every dependency reports as active.
+    pub fn is_healthy(&self) -> bool {
+        self.dependencies
+            .iter()
+            .all(|dependency| dependency.status == DependencyStatus::Active)
+    }
+}
+
+/// Defines a service the Connect server depends on
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct ServiceDependency {
+    /// The name of the service, i.e. `sync` or `sqlite`.
+    pub service: String,
+    /// The state of the service.
+    pub status: DependencyStatus,
+    /// Some optional text describing the state.
+    pub message: Option<String>,
+}
+
+/// Describes the state of a service dependency
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DependencyStatus {
+    /// The service is up and running
+    Active,
+    /// The service is waiting for a valid API token before it can sync
+    TokenNeeded,
+    /// A state not known to this SDK
+    #[serde(other)]
+    Unknown,
+}
+
+/// Defines the Prometheus metrics exposed by a Connect server
+#[derive(Debug, PartialEq, Default)]
+pub struct Metrics {
+    /// Every sample found in the exposition.
+    pub samples: Vec<MetricSample>,
+}
+
+/// Defines a single Prometheus sample
+#[derive(Debug, PartialEq, Clone)]
+pub struct MetricSample {
+    /// The metric name.
+    pub name: String,
+    /// The labels attached to the sample.
+    pub labels: BTreeMap<String, String>,
+    /// The sample value.
+    pub value: f64,
+}
+
+impl Metrics {
+    /// Parse metrics in the Prometheus text exposition format, lines which cannot be parsed are
+    /// skipped.
+    pub fn parse(text: &str) -> Self {
+        let samples = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(parse_sample)
+            .collect();
+
+        Self { samples }
+    }
+
+    /// Returns all samples with the given metric name.
+    pub fn get<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a MetricSample> + 'a {
+        self.samples
+            .iter()
+            .filter(move |sample| sample.name == name)
+    }
+}
+
+fn parse_sample(line: &str) -> Option<MetricSample> {
+    let (name, labels, rest) = match line.find('{') {
+        Some(start) => {
+            let end = start + line[start..].rfind('}')?;
+            let labels = parse_labels(&line[start + 1..end])?;
+
+            (&line[..start], labels, &line[end + 1..])
+        }
+        None => {
+            let (name, rest) = line.split_once(char::is_whitespace)?;
+
+            (name, BTreeMap::new(), rest)
+        }
+    };
+
+    // A timestamp may follow the value, it is ignored.
+    let value = rest.split_whitespace().next()?;
+    let value = match value {
+        "+Inf" => f64::INFINITY,
+        "-Inf" => f64::NEG_INFINITY,
+        value => value.parse().ok()?,
+    };
+
+    Some(MetricSample {
+        name: name.trim().to_string(),
+        labels,
+        value,
+    })
+}
+
+fn parse_labels(input: &str) -> Option<BTreeMap<String, String>> {
+    let mut labels = BTreeMap::new();
+    let mut chars = input.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+        if chars.peek().is_none() {
+            return Some(labels);
+        }
+
+        let key: String = std::iter::from_fn(|| chars.next_if(|c| *c != '=')).collect();
+        chars.next()?;
+        if chars.next()? != '"' {
+            return None;
+        }
+
+        let mut value = String::new();
+        loop {
+            match chars.next()? {
+                '\\' => match chars.next()? {
+                    'n' => value.push('\n'),
+                    other => value.push(other),
+                },
+                '"' => break,
+                other => value.push(other),
+            }
+        }
+
+        labels.insert(key.trim().to_string(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Metrics;
+
+    #[test]
+    fn parse_metrics() {
+        let text = r#"
+# HELP go_goroutines Number of goroutines that currently exist.
+# TYPE go_goroutines gauge
+go_goroutines 21
+http_requests_total{method="GET",path="/v1/vaults",code="200"} 3 1395066363000
+http_request_duration_seconds_bucket{le="+Inf",note="a \"quoted\" value"} +Inf
+"#;
+        let metrics = Metrics::parse(text);
+
+        assert_eq!(metrics.samples.len(), 3);
+        assert_eq!(metrics.get("go_goroutines").next().unwrap().value, 21.0);
+
+        let requests = metrics.get("http_requests_total").next().unwrap();
+        assert_eq!(requests.labels["path"], "/v1/vaults");
+        assert_eq!(requests.value, 3.0);
+
+        let bucket = metrics
+            .get("http_request_duration_seconds_bucket")
+            .next()
+            .unwrap();
+        assert_eq!(bucket.labels["note"], "a \"quoted\" value");
+        assert!(bucket.value.is_infinite());
+    }
+}
@@ -0,0 +1,172 @@
+//! Environment loading
+
+use super::{resolve_all, unresolved, SecretReference};
+use crate::{client::HTTPClient, error::Error};
+use std::{collections::BTreeMap, path::Path};
+
+/// Read a `.env`-style file, resolving every value which is an `op://` reference
+///
+/// Other values are returned as written. References are resolved concurrently, and if any of
+/// them cannot be parsed or found the error lists all of them.
+///
+/// The process environment is left unchanged, as setting variables while other threads may read
+/// them is unsound. Pass the variables to child processes with [`Command::envs`] instead.
+///
+/// [`Command::envs`]: std::process::Command::envs
+///
+/// ```no_run
+/// # #[cfg(feature = "hyper-client")]
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), connect_1password::error::Error> {
+/// use connect_1password::{client::ClientBuilder, secrets};
+///
+/// // DATABASE_PASSWORD=op://Production/Database/password
+/// let client = ClientBuilder::from_env()?.build()?;
+/// let env = secrets::env_from_path(&client, ".env.production").await?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "hyper-client"))]
+/// # fn main() {}
+/// ```
+pub async fn env_from_path(
+    client: &impl HTTPClient,
+    path: impl AsRef<Path>,
+) -> Result<BTreeMap<String, String>, Error> {
+    let entries = dotenvy::from_path_iter(path.as_ref())
+        .map_err(Error::new_config_error)?
+        .collect::<Result<Vec<(String, String)>, _>>()
+        .map_err(Error::new_config_error)?;
+
+    resolve_env(client, entries).await
+}
+
+/// Resolve every value of `vars` which is an `op://` reference, like [`env_from_path`]
+///
+/// Use this for variables which do not come from a file, i.e. the process environment.
+pub async fn resolve_env(
+    client: &impl HTTPClient,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<BTreeMap<String, String>, Error> {
+    let mut env = BTreeMap::new();
+    let mut references = vec![];
+    let mut missing = vec![];
+    for (key, value) in vars {
+        if !value.starts_with(super::SCHEME) {
+            env.insert(key, value);
+            continue;
+        }
+
+        match SecretReference::parse(&value) {
+            Ok(reference) => references.push((key, reference)),
+            Err(err) => missing.push((format!("{}={}", key, value), err)),
+        }
+    }
+
+    let parsed: Vec<SecretReference> = references
+        .iter()
+        .map(|(_, reference)| reference.clone())
+        .collect();
+    let results = resolve_all(client, &parsed).await;
+    for ((key, reference), result) in references.into_iter().zip(results) {
+        match result {
+            Ok(value) => {
+                env.insert(key, value);
+            }
+            Err(err) if err.is_not_found() => missing.push((format!("{}={}", key, reference), err)),
+            Err(err) => return Err(err),
+        }
+    }
+
+    if !missing.is_empty() {
+        return Err(unresolved(missing));
+    }
+
+    Ok(env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::env_from_path;
+    use crate::{
+        error::ErrorKind,
+        get_test_client, items,
+        models::item::{FullItem, ItemBuilder, ItemCategory, LoginItem},
+    };
+
+    fn write_env(name: &str, contents: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("connect-1password-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+
+        path
+    }
+
+    #[tokio::test]
+    async fn loads_references() {
+        let (client, test_vault_id) = get_test_client();
+
+        let item: FullItem = ItemBuilder::new(&test_vault_id, ItemCategory::Login)
+            .title("Database")
+            .username("admin")
+            .password("")
+            .build()
+            .unwrap();
+        items::add(&client, item).await.unwrap();
+
+        let path = write_env(
+            "loads_references.env",
+            "# Database\nDATABASE_USER=op://connect-1password/Database/username\nDATABASE_HOST=db.internal\n",
+        );
+        let env = env_from_path(&client, &path).await.unwrap();
+
+        assert_eq!(env["DATABASE_USER"], "admin");
+        assert_eq!(env["DATABASE_HOST"], "db.internal");
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn lists_every_unresolved_reference() {
+        let (client, _test_vault_id) = get_test_client();
+
+        let path = write_env(
+            "lists_every_unresolved_reference.env",
+            "A=op://connect-1password/Missing/password\nB=op://Nope/Item/field\nC=op://invalid\n",
+        );
+        let err = env_from_path(&client, &path).await.unwrap_err();
+        let message = err.to_string();
+
+        assert_eq!(err.kind(), ErrorKind::Unresolved);
+        assert_eq!(err.status(), None);
+        let unresolved = err.unresolved().unwrap();
+        assert_eq!(unresolved.len(), 3);
+        for (key, kind) in [
+            ("A=", ErrorKind::NotFound),
+            ("B=", ErrorKind::NotFound),
+            ("C=", ErrorKind::Parsing),
+        ] {
+            assert!(message.contains(key), "{}", message);
+            let (_, err) = unresolved
+                .iter()
+                .find(|(reference, _)| reference.starts_with(key))
+                .unwrap();
+            assert_eq!(err.kind(), kind);
+        }
+
+        let path = write_env(
+            "lists_every_missing_reference.env",
+            "A=op://connect-1password/Missing/password\nB=op://Nope/Item/field\n",
+        );
+        let err = env_from_path(&client, &path).await.unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::Unresolved);
+        assert!(err
+            .unresolved()
+            .unwrap()
+            .iter()
+            .all(|(_, err)| err.is_not_found()));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
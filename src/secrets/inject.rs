@@ -0,0 +1,181 @@
+//! Template injection
+
+use super::{resolve_all, unresolved, SecretReference};
+use crate::{client::HTTPClient, error::Error};
+use std::{collections::HashMap, ops::Range};
+
+/// Describes how [`inject`] handles references which cannot be found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InjectMode {
+    /// Fail, listing every reference which could not be found
+    #[default]
+    Strict,
+    /// Leave the placeholders of references which could not be found untouched
+    Lenient,
+}
+
+/// A `{{ op://... }}` placeholder found in a template
+struct Placeholder<'a> {
+    range: Range<usize>,
+    reference: &'a str,
+}
+
+/// Render `template`, replacing every `{{ op://vault/item/[section/]field }}` placeholder with
+/// the value of the field, like `op inject`
+///
+/// References are resolved concurrently, each distinct reference once. Other `{{ ... }}`
+/// expressions are left untouched, so templates may be rendered further by other tools.
+/// Malformed references, and errors other than a missing vault, item or field, fail in either
+/// mode.
+///
+/// ```no_run
+/// # #[cfg(feature = "hyper-client")]
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), connect_1password::error::Error> {
+/// use connect_1password::{client::ClientBuilder, secrets::{self, InjectMode}};
+///
+/// let client = ClientBuilder::from_env()?.build()?;
+/// let template = "database_url = postgres://app:{{ op://Production/Database/password }}@db";
+///
+/// let rendered = secrets::inject(&client, template, InjectMode::Strict).await?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "hyper-client"))]
+/// # fn main() {}
+/// ```
+pub async fn inject(
+    client: &impl HTTPClient,
+    template: &str,
+    mode: InjectMode,
+) -> Result<String, Error> {
+    let placeholders = placeholders(template);
+
+    let mut references: Vec<&str> = placeholders.iter().map(|p| p.reference).collect();
+    references.sort_unstable();
+    references.dedup();
+    let parsed = references
+        .iter()
+        .map(|reference| SecretReference::parse(reference))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut values = HashMap::new();
+    let mut missing = vec![];
+    for (reference, result) in references.iter().zip(resolve_all(client, &parsed).await) {
+        match result {
+            Ok(value) => {
+                values.insert(*reference, value);
+            }
+            Err(err) if err.is_not_found() => missing.push((reference.to_string(), err)),
+            Err(err) => return Err(err),
+        }
+    }
+    if mode == InjectMode::Strict && !missing.is_empty() {
+        return Err(unresolved(missing));
+    }
+
+    let mut rendered = String::with_capacity(template.len());
+    let mut position = 0;
+    for placeholder in &placeholders {
+        rendered.push_str(&template[position..placeholder.range.start]);
+        match values.get(placeholder.reference) {
+            Some(value) => rendered.push_str(value),
+            None => rendered.push_str(&template[placeholder.range.clone()]),
+        }
+        position = placeholder.range.end;
+    }
+    rendered.push_str(&template[position..]);
+
+    Ok(rendered)
+}
+
+/// Find every `{{ op://... }}` placeholder, in order
+fn placeholders(template: &str) -> Vec<Placeholder<'_>> {
+    let mut placeholders = vec![];
+    let mut position = 0;
+
+    while let Some(open) = template[position..].find("{{") {
+        let start = position + open;
+        let close = match template[start + 2..].find("}}") {
+            Some(close) => start + 2 + close,
+            None => break,
+        };
+
+        let reference = template[start + 2..close].trim();
+        if reference.starts_with(super::SCHEME) {
+            placeholders.push(Placeholder {
+                range: start..close + 2,
+                reference,
+            });
+        }
+        position = close + 2;
+    }
+
+    placeholders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{inject, placeholders, InjectMode};
+    use crate::{
+        get_test_client, items,
+        models::item::{ApiCredentialItem, FullItem, ItemBuilder, ItemCategory},
+    };
+
+    #[test]
+    fn finds_placeholders() {
+        let template =
+            "a={{ op://v/i/f }} b={{op://v/i/s/f?attribute=otp}} c={{ name }} d={{ op://x";
+
+        let found: Vec<_> = placeholders(template)
+            .iter()
+            .map(|placeholder| (placeholder.reference, &template[placeholder.range.clone()]))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                ("op://v/i/f", "{{ op://v/i/f }}"),
+                (
+                    "op://v/i/s/f?attribute=otp",
+                    "{{op://v/i/s/f?attribute=otp}}"
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn injects_secrets() {
+        let (client, test_vault_id) = get_test_client();
+
+        let item: FullItem = ItemBuilder::new(&test_vault_id, ItemCategory::ApiCredential)
+            .api_key("lawyer-rottenborn", "Dell XYZ")
+            .build()
+            .unwrap();
+        let (new_item, _) = items::add(&client, item).await.unwrap();
+        let (item, _) = items::get(&client, &test_vault_id, &new_item.id, false)
+            .await
+            .unwrap();
+        let field_id = item.fields[0].id.clone().unwrap();
+
+        let template = format!(
+            "key={{{{ op://connect-1password/Dell XYZ/API Key/{} }}}}\nagain={{{{op://connect-1password/Dell XYZ/API Key/{}}}}}\nmissing={{{{ op://connect-1password/Nope/key }}}}\n",
+            field_id, field_id
+        );
+
+        let err = inject(&client, &template, InjectMode::Strict)
+            .await
+            .unwrap_err();
+        let unresolved = err.unresolved().unwrap();
+        assert_eq!(unresolved.len(), 1);
+        assert!(unresolved[0].1.is_not_found());
+        assert!(err.to_string().contains("op://connect-1password/Nope/key"));
+
+        let rendered = inject(&client, &template, InjectMode::Lenient)
+            .await
+            .unwrap();
+        assert_eq!(
+            rendered,
+            "key=lawyer-rottenborn\nagain=lawyer-rottenborn\nmissing={{ op://connect-1password/Nope/key }}\n"
+        );
+    }
+}
@@ -0,0 +1,465 @@
+//! Secret references
+//!
+//! Resolve `op://vault/item/[section/]field` references, as used by 1Password's own tooling, to
+//! the value of a field. Vaults, items, sections and fields are looked up by ID or by name; vaults
+//! and items are fetched directly when given by ID, and otherwise found with a server-side
+//! [`Filter`] on their exact name.
+//! Use [`inject`] to render templates containing `{{ op://... }}` placeholders, and
+//! [`env_from_path`] to load `.env` files whose values are references.
+//!
+//! ```
+//! use connect_1password::secrets::{Attribute, SecretReference};
+//!
+//! let reference: SecretReference = "op://Production/Database/admin/password".parse()?;
+//!
+//! assert_eq!(reference.vault, "Production");
+//! assert_eq!(reference.section.as_deref(), Some("admin"));
+//! assert_eq!(reference.attribute, Attribute::Value);
+//! # Ok::<(), connect_1password::error::Error>(())
+//! ```
+
+use crate::error::{CustomError, Error};
+use crate::{
+    client::HTTPClient,
+    filter::Filter,
+    items,
+    models::item::{FieldObject, FullItem},
+    vaults,
+};
+use std::{fmt, str::FromStr};
+
+mod env;
+mod inject;
+
+pub use env::{env_from_path, resolve_env};
+pub use inject::{inject, InjectMode};
+
+const SCHEME: &str = "op://";
+
+/// Describes which attribute of a field a reference resolves to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Attribute {
+    /// The value of the field
+    #[default]
+    Value,
+    /// The current one-time password of an `OTP` field, `otp` or `totp`
+    Otp,
+    /// The type of the field
+    Type,
+    /// The ID of the field
+    Id,
+    /// The label of the field
+    Label,
+    /// The purpose of the field, such as `PASSWORD`
+    Purpose,
+}
+
+impl Attribute {
+    /// Returns the attribute as written in a reference, i.e. `"otp"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Value => "value",
+            Self::Otp => "otp",
+            Self::Type => "type",
+            Self::Id => "id",
+            Self::Label => "label",
+            Self::Purpose => "purpose",
+        }
+    }
+}
+
+impl FromStr for Attribute {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "value" => Ok(Self::Value),
+            "otp" | "totp" => Ok(Self::Otp),
+            "type" => Ok(Self::Type),
+            "id" => Ok(Self::Id),
+            "label" => Ok(Self::Label),
+            "purpose" => Ok(Self::Purpose),
+            _ => Err(invalid(&format!("unknown attribute {}", s))),
+        }
+    }
+}
+
+/// This is a parsed `op://vault/item/[section/]field[?attribute=...]` reference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretReference {
+    /// The name or ID of the vault.
+    pub vault: String,
+    /// The title or ID of the item.
+    pub item: String,
+    /// The label or ID of the section containing the field, if any.
+    pub section: Option<String>,
+    /// The label or ID of the field.
+    pub field: String,
+    /// The attribute of the field to resolve.
+    pub attribute: Attribute,
+}
+
+impl SecretReference {
+    /// Parse a reference, such as `op://Production/Database/password?attribute=type`
+    pub fn parse(reference: &str) -> Result<Self, Error> {
+        let path = reference
+            .strip_prefix(SCHEME)
+            .ok_or_else(|| invalid("references start with op://"))?;
+        let (path, query) = match path.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (path, None),
+        };
+
+        let segments: Vec<&str> = path.split('/').collect();
+        if segments.iter().any(|segment| segment.trim().is_empty()) {
+            return Err(invalid("vault, item, section and field must not be empty"));
+        }
+        let (vault, item, section, field) = match segments.as_slice() {
+            [vault, item, field] => (vault, item, None, field),
+            [vault, item, section, field] => (vault, item, Some(section.to_string()), field),
+            _ => return Err(invalid("expected op://vault/item/[section/]field")),
+        };
+
+        let mut attribute = Attribute::default();
+        for pair in query
+            .unwrap_or_default()
+            .split('&')
+            .filter(|p| !p.is_empty())
+        {
+            match pair.split_once('=') {
+                Some(("attribute", value)) => attribute = value.parse()?,
+                _ => return Err(invalid(&format!("unsupported query parameter {}", pair))),
+            }
+        }
+
+        Ok(Self {
+            vault: vault.to_string(),
+            item: item.to_string(),
+            section,
+            field: field.to_string(),
+            attribute,
+        })
+    }
+
+    /// Look up the referenced field and return the requested attribute
+    pub async fn resolve(&self, client: &impl HTTPClient) -> Result<String, Error> {
+        let vault_id = self.vault_id(client).await?;
+        let item = self.item(client, &vault_id).await?;
+
+        let field = self.select_field(&item)?;
+        let value = match self.attribute {
+            Attribute::Value => field.value.clone(),
+            Attribute::Otp => field.totp.clone(),
+            Attribute::Type => field.r#type.clone(),
+            Attribute::Id => field.id.clone(),
+            Attribute::Label => field.label.clone(),
+            Attribute::Purpose => field.purpose.clone(),
+        };
+
+        value.ok_or_else(|| {
+            not_found(&format!(
+                "Field {} has no {}",
+                self.field,
+                self.attribute.as_str()
+            ))
+        })
+    }
+
+    async fn vault_id(&self, client: &impl HTTPClient) -> Result<String, Error> {
+        if looks_like_id(&self.vault) {
+            match vaults::get(client, &self.vault).await {
+                Ok((vault, _)) => return Ok(vault.id),
+                // A vault may be named like an ID
+                Err(err) if err.is_not_found() => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        let (vaults, _) = vaults::all_filtered(client, &Filter::name_eq(&self.vault)).await?;
+        // Narrowed by the server, the filter is checked again in case it matches more loosely
+        let vault = find_one(
+            &vaults,
+            &self.vault,
+            |_| false,
+            |vault| vault.name == self.vault,
+        )
+        .ok_or_else(|| not_found(&format!("Vault {} not found", self.vault)))??;
+
+        Ok(vault.id.clone())
+    }
+
+    async fn item(&self, client: &impl HTTPClient, vault_id: &str) -> Result<FullItem, Error> {
+        if looks_like_id(&self.item) {
+            match items::get(client, vault_id, &self.item, false).await {
+                Ok((item, _)) => return Ok(item),
+                Err(err) if err.is_not_found() => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        let (items, _) =
+            items::all_filtered(client, vault_id, &Filter::title_eq(&self.item)).await?;
+        let summary = find_one(
+            &items,
+            &self.item,
+            |_| false,
+            |item| item.title == self.item,
+        )
+        .ok_or_else(|| {
+            not_found(&format!(
+                "Item {} not found in vault {}",
+                self.item, self.vault
+            ))
+        })??;
+        let (item, _) = items::get(client, vault_id, &summary.id, false).await?;
+
+        Ok(item)
+    }
+
+    fn select_field<'a>(&self, item: &'a FullItem) -> Result<&'a FieldObject, Error> {
+        let section_id = match &self.section {
+            Some(section) => {
+                let found = find_one(
+                    &item.sections,
+                    section,
+                    |candidate| candidate.id == *section,
+                    |candidate| {
+                        candidate
+                            .label
+                            .as_deref()
+                            .is_some_and(|label| label.eq_ignore_ascii_case(section))
+                    },
+                )
+                .ok_or_else(|| not_found(&format!("Section {} not found", section)))??;
+                Some(found.id.as_str())
+            }
+            None => None,
+        };
+
+        let in_section = |field: &&FieldObject| match section_id {
+            Some(id) => field
+                .section
+                .as_ref()
+                .is_some_and(|section| section.id == id),
+            None => true,
+        };
+        let fields: Vec<&FieldObject> = item.fields.iter().filter(in_section).collect();
+
+        find_one(
+            &fields,
+            &self.field,
+            |field| field.id.as_deref() == Some(self.field.as_str()),
+            |field| {
+                field
+                    .label
+                    .as_deref()
+                    .is_some_and(|label| label.eq_ignore_ascii_case(&self.field))
+            },
+        )
+        .ok_or_else(|| not_found(&format!("Field {} not found", self.field)))?
+        .copied()
+    }
+}
+
+impl FromStr for SecretReference {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for SecretReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}/{}/", SCHEME, self.vault, self.item)?;
+        if let Some(section) = &self.section {
+            write!(f, "{}/", section)?;
+        }
+        write!(f, "{}", self.field)?;
+        if self.attribute != Attribute::Value {
+            write!(f, "?attribute={}", self.attribute.as_str())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolve a reference such as `op://Production/Database/password` to the value of the field
+pub async fn resolve(client: &impl HTTPClient, reference: &str) -> Result<String, Error> {
+    SecretReference::parse(reference)?.resolve(client).await
+}
+
+/// Resolve several references concurrently, returning the results in the same order
+pub(crate) async fn resolve_all(
+    client: &impl HTTPClient,
+    references: &[SecretReference],
+) -> Vec<Result<String, Error>> {
+    futures::future::join_all(references.iter().map(|reference| reference.resolve(client))).await
+}
+
+/// Returns a single error listing every reference in `missing` with the reason it was not
+/// resolved, see [`Error::unresolved`].
+pub(crate) fn unresolved(missing: Vec<(String, Error)>) -> Error {
+    Error::new_unresolved_error(missing)
+}
+
+/// Returns true if `segment` has the form of a 1Password ID, 26 lowercase letters and digits
+fn looks_like_id(segment: &str) -> bool {
+    segment.len() == 26
+        && segment
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+}
+
+/// Find the entry matching by ID, or otherwise the only entry matching by name
+fn find_one<'a, T>(
+    entries: &'a [T],
+    name: &str,
+    by_id: impl Fn(&T) -> bool,
+    by_name: impl Fn(&T) -> bool,
+) -> Option<Result<&'a T, Error>> {
+    if let Some(entry) = entries.iter().find(|entry| by_id(entry)) {
+        return Some(Ok(entry));
+    }
+
+    let mut matches = entries.iter().filter(|entry| by_name(entry));
+    let first = matches.next()?;
+    match matches.next() {
+        None => Some(Ok(first)),
+        Some(_) => Some(Err(CustomError::new(&format!(
+            "More than one entry is named {}, use its ID instead",
+            name
+        ))
+        .into())),
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new_parsing_error(CustomError::new(&format!(
+        "Invalid secret reference: {}",
+        message
+    )))
+}
+
+fn not_found(message: &str) -> Error {
+    Error::new_not_found_error(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Attribute, SecretReference};
+    use crate::{
+        error::ErrorKind,
+        get_test_client, items,
+        models::item::{
+            FieldObject, ItemBuilder, ItemCategory, LoginItem, SectionID, SectionObject,
+        },
+        secrets,
+        testing::{Expectation, MockClient},
+    };
+    use serde_json::json;
+
+    #[test]
+    fn parses_references() {
+        let reference =
+            SecretReference::parse("op://Prod/Database/admin/password?attribute=totp").unwrap();
+
+        assert_eq!(
+            reference,
+            SecretReference {
+                vault: "Prod".to_string(),
+                item: "Database".to_string(),
+                section: Some("admin".to_string()),
+                field: "password".to_string(),
+                attribute: Attribute::Otp,
+            }
+        );
+        assert_eq!(
+            reference.to_string(),
+            "op://Prod/Database/admin/password?attribute=otp"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_references() {
+        for reference in [
+            "Prod/Database/password",
+            "op://Prod/Database",
+            "op://Prod//password",
+            "op://a/b/c/d/e",
+            "op://Prod/Database/password?attribute=nope",
+            "op://Prod/Database/password?format=json",
+        ] {
+            assert!(SecretReference::parse(reference).is_err(), "{}", reference);
+        }
+    }
+
+    #[tokio::test]
+    async fn resolves_fields_by_name() {
+        let (client, test_vault_id) = get_test_client();
+
+        let mut builder = ItemBuilder::new(&test_vault_id, ItemCategory::Login)
+            .title("Database")
+            .username("admin")
+            .password("hunter2");
+        builder
+            .sections
+            .push(SectionObject::new("replica", "Replica"));
+        builder.fields.push(FieldObject {
+            label: Some("host".to_string()),
+            value: Some("db.internal".to_string()),
+            section: Some(SectionID {
+                id: "replica".to_string(),
+            }),
+            ..Default::default()
+        });
+        let item = builder.build().unwrap();
+        let (new_item, _) = items::add(&client, item).await.unwrap();
+
+        let host = secrets::resolve(&client, "op://connect-1password/Database/Replica/host")
+            .await
+            .unwrap();
+        assert_eq!(host, "db.internal");
+
+        let id = secrets::resolve(
+            &client,
+            "op://connect-1password/Database/username?attribute=id",
+        )
+        .await
+        .unwrap();
+        assert_eq!(id, "username");
+
+        let by_id = format!("op://{}/{}/username", test_vault_id, new_item.id);
+        let username = secrets::resolve(&client, &by_id).await.unwrap();
+        assert_eq!(username, "admin");
+
+        let err = secrets::resolve(&client, "op://connect-1password/Missing/password")
+            .await
+            .unwrap_err();
+        assert!(err.is_not_found());
+    }
+
+    #[tokio::test]
+    async fn checks_filtered_names() {
+        let client = MockClient::new();
+        client.expect(
+            Expectation::get(&["v1", "vaults"])
+                .query("filter", "name eq \"Production\"")
+                .respond_json(&json!([{
+                    "id": "vault",
+                    "name": "production",
+                    "attributeVersion": 1,
+                    "contentVersion": 1,
+                    "type": "USER_CREATED",
+                }])),
+        );
+
+        let err = secrets::resolve(&client, "op://Production/Database/password")
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(err.is_not_found());
+        client.verify();
+    }
+}
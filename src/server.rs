@@ -0,0 +1,76 @@
+//! Server status
+//!
+//! These endpoints are served outside of the `v1` API and are useful for readiness probes and
+//! startup checks.
+
+use crate::error::Error;
+use crate::{
+    client::{self, ConnectRequest, HTTPClient},
+    models::server::{Metrics, ServerHealth},
+};
+
+/// Check the server is up, succeeds if the server responds to its heartbeat
+pub async fn heartbeat(client: &impl HTTPClient) -> Result<(), Error> {
+    fetch_text(client, "heartbeat").await?;
+
+    Ok(())
+}
+
+/// Get the health of the server and its dependencies
+pub async fn health(client: &impl HTTPClient) -> Result<(ServerHealth, serde_json::Value), Error> {
+    let result = client
+        .send_request::<ServerHealth>(ConnectRequest::get(&["health"]))
+        .await?;
+
+    Ok(result)
+}
+
+/// Get the Prometheus metrics of the server
+pub async fn metrics(client: &impl HTTPClient) -> Result<(Metrics, String), Error> {
+    let text = fetch_text(client, "metrics").await?;
+
+    Ok((Metrics::parse(&text), text))
+}
+
+async fn fetch_text(client: &impl HTTPClient, endpoint: &str) -> Result<String, Error> {
+    let bytes = client::send_checked(client, ConnectRequest::get(&[endpoint]))
+        .await?
+        .bytes()
+        .await?;
+
+    String::from_utf8(bytes.to_vec()).map_err(Error::new_parsing_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{get_test_client, models::server::DependencyStatus, server};
+    use tokio::test;
+
+    #[test]
+    async fn heartbeat() {
+        let (client, _test_vault_id) = get_test_client();
+
+        server::heartbeat(&client).await.unwrap();
+    }
+
+    #[test]
+    async fn health() {
+        let (client, _test_vault_id) = get_test_client();
+
+        let (health, _) = server::health(&client).await.unwrap();
+
+        assert!(!health.name.is_empty());
+        assert!(health.is_healthy());
+        assert_eq!(health.sync().unwrap().status, DependencyStatus::Active);
+    }
+
+    #[test]
+    async fn metrics() {
+        let (client, _test_vault_id) = get_test_client();
+
+        let (metrics, text) = server::metrics(&client).await.unwrap();
+
+        assert!(!text.is_empty());
+        assert!(!metrics.samples.is_empty());
+    }
+}
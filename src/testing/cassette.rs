@@ -0,0 +1,500 @@
+//! Record and replay of Connect interactions
+
+use crate::client::{ConnectRequest, ConnectResponse, HTTPClient};
+use crate::error::{CustomError, Error};
+use crate::models::item::SECRET_FIELDS;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use bytes::Bytes;
+use http::{
+    header::{HeaderName, AUTHORIZATION, PROXY_AUTHORIZATION, SET_COOKIE},
+    HeaderMap, HeaderValue, StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
+
+/// Replaces secrets written to a cassette.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Selects recording or replaying with [`Cassette::from_env`].
+pub const CASSETTE_ENV: &str = "OP_CASSETTE";
+
+/// Headers which are never written to a cassette.
+const SECRET_HEADERS: &[HeaderName] = &[AUTHORIZATION, PROXY_AUTHORIZATION, SET_COOKIE];
+
+/// Describes whether a [`Cassette`] talks to a server or replays a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Send requests to the wrapped client and record them
+    Record,
+    /// Answer requests from the cassette file
+    Replay,
+}
+
+/// Represents an [`HTTPClient`] recording interactions with a real Connect server to a JSON
+/// cassette file, and replaying them later without a server or credentials.
+///
+/// Secrets are scrubbed when recording: the values of concealed, password and OTP fields,
+/// inline file contents, credential headers and any string registered with
+/// [`Cassette::redact`], such as the API token. Replays answer each request with the first
+/// unused interaction of the same method and path.
+///
+/// ```no_run
+/// # #[cfg(feature = "hyper-client")]
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), connect_1password::error::Error> {
+/// use connect_1password::{client::ClientBuilder, testing::Cassette, vaults};
+///
+/// // Records with `OP_CASSETTE=record`, replays otherwise
+/// let client = Cassette::from_env("tests/cassettes/vaults.json", || {
+///     ClientBuilder::from_env()?.build()
+/// })?;
+///
+/// let (vaults, _) = vaults::all(&client).await?;
+///
+/// client.save()?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "hyper-client"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug)]
+pub struct Cassette<C> {
+    inner: Option<C>,
+    path: PathBuf,
+    redactions: Vec<String>,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    interactions: Vec<Interaction>,
+    used: Vec<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct File {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<RecordedBody>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<RecordedBody>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordedBody {
+    Json(Value),
+    Text(String),
+    Base64(String),
+}
+
+impl<C: HTTPClient> Cassette<C> {
+    /// Create an instance sending requests with `inner` and recording them for [`Cassette::save`]
+    pub fn record(inner: C, path: impl AsRef<Path>) -> Self {
+        Self {
+            inner: Some(inner),
+            path: path.as_ref().to_path_buf(),
+            redactions: vec![],
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Create an instance answering requests from the cassette at `path`
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let json = std::fs::read(&path).map_err(Error::new_config_error)?;
+        let file: File = serde_json::from_slice(&json).map_err(Error::new_parsing_error)?;
+
+        Ok(Self {
+            inner: None,
+            path,
+            redactions: vec![],
+            state: Mutex::new(State {
+                used: vec![false; file.interactions.len()],
+                interactions: file.interactions,
+            }),
+        })
+    }
+
+    /// Record when `OP_CASSETTE` is set to `record`, creating the client with `connect`, and
+    /// replay from `path` otherwise. The `OP_API_TOKEN` is scrubbed from recordings.
+    pub fn from_env(
+        path: impl AsRef<Path>,
+        connect: impl FnOnce() -> Result<C, Error>,
+    ) -> Result<Self, Error> {
+        let record = std::env::var(CASSETTE_ENV).is_ok_and(|mode| mode == "record");
+
+        if !record {
+            return Self::replay(path);
+        }
+
+        let cassette = Self::record(connect()?, path);
+        match std::env::var("OP_API_TOKEN") {
+            Ok(token) => Ok(cassette.redact(&token)),
+            Err(_) => Ok(cassette),
+        }
+    }
+
+    /// Scrub every occurrence of `secret` from recorded paths, headers and bodies.
+    pub fn redact(mut self, secret: &str) -> Self {
+        if !secret.is_empty() {
+            self.redactions.push(secret.to_string());
+        }
+        self
+    }
+
+    /// Returns whether requests are recorded or replayed.
+    pub fn mode(&self) -> Mode {
+        match self.inner {
+            Some(_) => Mode::Record,
+            None => Mode::Replay,
+        }
+    }
+
+    /// Write the recorded interactions to the cassette file, creating its directory if needed.
+    /// This does nothing when replaying.
+    pub fn save(&self) -> Result<(), Error> {
+        if self.mode() == Mode::Replay {
+            return Ok(());
+        }
+
+        let file = File {
+            interactions: self.state().interactions.clone(),
+        };
+        let json = serde_json::to_vec_pretty(&file)?;
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(Error::new_config_error)?;
+        }
+        std::fs::write(&self.path, json).map_err(Error::new_config_error)
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("cassette state")
+    }
+
+    fn scrub(&self, text: &str) -> String {
+        self.redactions
+            .iter()
+            .fold(text.to_string(), |text, secret| {
+                text.replace(secret, REDACTED)
+            })
+    }
+
+    fn scrub_headers(&self, headers: &HeaderMap) -> BTreeMap<String, String> {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = if SECRET_HEADERS.contains(name) {
+                    REDACTED.to_string()
+                } else {
+                    self.scrub(&String::from_utf8_lossy(value.as_bytes()))
+                };
+
+                (name.to_string(), value)
+            })
+            .collect()
+    }
+
+    fn scrub_body(&self, body: &[u8]) -> Option<RecordedBody> {
+        if body.is_empty() {
+            return None;
+        }
+
+        let recorded = match serde_json::from_slice::<Value>(body) {
+            Ok(mut json) => {
+                redact_json(&mut json);
+                RecordedBody::Json(json)
+            }
+            Err(_) => match std::str::from_utf8(body) {
+                Ok(text) => RecordedBody::Text(text.to_string()),
+                Err(_) => RecordedBody::Base64(STANDARD.encode(body)),
+            },
+        };
+
+        // Literal secrets are scrubbed from the serialized form, wherever they appear
+        let json = serde_json::to_string(&recorded).ok()?;
+        serde_json::from_str(&self.scrub(&json)).ok()
+    }
+
+    async fn record_interaction(
+        &self,
+        inner: &C,
+        request: ConnectRequest,
+    ) -> Result<ConnectResponse, Error> {
+        let recorded_request = RecordedRequest {
+            method: request.method().to_string(),
+            path: self.scrub(&request.path_and_query()),
+            headers: self.scrub_headers(request.headers()),
+            body: request.body_bytes().and_then(|body| self.scrub_body(body)),
+        };
+
+        let response = inner.execute(request).await?;
+        let status = response.status;
+        let headers = response.headers.clone();
+        let body = response.bytes().await?;
+
+        self.state().interactions.push(Interaction {
+            request: recorded_request,
+            response: RecordedResponse {
+                status: status.as_u16(),
+                headers: self.scrub_headers(&headers),
+                body: self.scrub_body(&body),
+            },
+        });
+
+        let mut response = ConnectResponse::new(status, body);
+        response.headers = headers;
+
+        Ok(response)
+    }
+
+    fn replay_interaction(&self, request: &ConnectRequest) -> Result<ConnectResponse, Error> {
+        let method = request.method().to_string();
+        let path = self.scrub(&request.path_and_query());
+
+        let mut state = self.state();
+        let State { interactions, used } = &mut *state;
+        let position = interactions
+            .iter()
+            .zip(used.iter())
+            .position(|(interaction, used)| {
+                !used && interaction.request.method == method && interaction.request.path == path
+            })
+            .ok_or_else(|| {
+                CustomError::new(&format!(
+                    "No recorded interaction for {} {} in {}",
+                    method,
+                    path,
+                    self.path.display()
+                ))
+            })?;
+        used[position] = true;
+
+        interactions[position].response.to_response()
+    }
+}
+
+#[async_trait]
+impl<C: HTTPClient> HTTPClient for Cassette<C> {
+    async fn execute(&self, request: ConnectRequest) -> Result<ConnectResponse, Error> {
+        match &self.inner {
+            Some(inner) => self.record_interaction(inner, request).await,
+            None => self.replay_interaction(&request),
+        }
+    }
+}
+
+impl RecordedResponse {
+    fn to_response(&self) -> Result<ConnectResponse, Error> {
+        let status = StatusCode::from_u16(self.status).map_err(Error::new_parsing_error)?;
+        let body = match &self.body {
+            None => Bytes::new(),
+            Some(RecordedBody::Json(json)) => Bytes::from(serde_json::to_vec(json)?),
+            Some(RecordedBody::Text(text)) => Bytes::from(text.clone()),
+            Some(RecordedBody::Base64(data)) => {
+                Bytes::from(STANDARD.decode(data).map_err(Error::new_parsing_error)?)
+            }
+        };
+
+        let mut response = ConnectResponse::new(status, body);
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                response.headers.append(name, value);
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+/// Scrub the values of secret fields, of JSON Patch operations on fields and inline file contents
+fn redact_json(json: &mut Value) {
+    match json {
+        Value::Object(object) => {
+            let secret = ["type", "purpose"].iter().any(|key| {
+                object
+                    .get(*key)
+                    .and_then(Value::as_str)
+                    .is_some_and(|kind| SECRET_FIELDS.contains(&kind))
+            });
+            // The operation does not say whether the field is concealed
+            let field_patch = object.contains_key("op")
+                && object
+                    .get("path")
+                    .and_then(Value::as_str)
+                    .is_some_and(|path| path.starts_with("/fields"));
+            if field_patch {
+                if let Some(value) = object.get_mut("value") {
+                    *value = Value::from(REDACTED);
+                }
+            }
+            if secret {
+                // `totp` holds the current code of OTP fields
+                for key in ["value", "totp"] {
+                    if let Some(value) = object.get_mut(key) {
+                        *value = Value::from(REDACTED);
+                    }
+                }
+            }
+            if object.contains_key("content_path") {
+                if let Some(content) = object.get_mut("content") {
+                    *content = Value::from(STANDARD.encode(REDACTED));
+                }
+            }
+
+            object.values_mut().for_each(redact_json);
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cassette, Mode, REDACTED};
+    use crate::{
+        client::HTTPClient,
+        items,
+        models::{
+            item::{FullItem, ItemBuilder, ItemCategory, LoginItem},
+            patch::ItemPatch,
+        },
+        testing::{Expectation, MockClient},
+    };
+    use serde_json::json;
+    use tokio::test;
+
+    fn cassette_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir()
+            .join(format!("connect-1password-{}", std::process::id()))
+            .join(name)
+    }
+
+    #[test]
+    async fn records_and_replays_redacted_interactions() {
+        let path = cassette_path("items.json");
+        let item: FullItem = ItemBuilder::new("vault", ItemCategory::Login)
+            .title("Login")
+            .username("Bob")
+            .password("")
+            .build()
+            .unwrap();
+
+        let mock = MockClient::new();
+        mock.expect(
+            Expectation::post(&["v1", "vaults", "vault", "items"]).respond_json(&json!({
+                "id": "item",
+                "title": "Login",
+                "category": "LOGIN",
+                "vault": { "id": "vault" },
+                "fields": [
+                    { "id": "username", "purpose": "USERNAME", "value": "Bob" },
+                    { "id": "password", "purpose": "PASSWORD", "value": "hunter2" },
+                    { "id": "note", "type": "STRING", "value": "token-123 inside" },
+                    { "id": "otp", "type": "OTP", "value": "otpauth://totp/x?secret=JBSWY3DP", "totp": "492039" },
+                ],
+            })),
+        );
+
+        let recorder = Cassette::record(mock, &path).redact("token-123");
+        assert_eq!(recorder.mode(), Mode::Record);
+        let (_, recorded) = items::add(&recorder, item).await.unwrap();
+        assert_eq!(recorded["fields"][1]["value"], "hunter2");
+        recorder.save().unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(!written.contains("hunter2"));
+        assert!(!written.contains("token-123"));
+        assert!(!written.contains("JBSWY3DP"));
+        assert!(!written.contains("492039"));
+
+        let player = Cassette::<MockClient>::replay(&path).unwrap();
+        let item: FullItem = ItemBuilder::new("vault", ItemCategory::Login)
+            .title("Login")
+            .username("Bob")
+            .password("")
+            .build()
+            .unwrap();
+        let (replayed, raw) = items::add(&player, item).await.unwrap();
+
+        assert_eq!(replayed.id, "item");
+        assert_eq!(raw["fields"][0]["value"], "Bob");
+        assert_eq!(raw["fields"][1]["value"], REDACTED);
+        assert_eq!(raw["fields"][2]["value"], "[REDACTED] inside");
+        assert_eq!(raw["fields"][3]["value"], REDACTED);
+        assert_eq!(raw["fields"][3]["totp"], REDACTED);
+
+        // Every interaction is replayed once
+        assert!(player
+            .send_request::<serde_json::Value>(crate::client::ConnectRequest::post(&[
+                "v1", "vaults", "vault", "items"
+            ]))
+            .await
+            .is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    async fn redacts_patched_field_values() {
+        let path = cassette_path("patch.json");
+
+        let mock = MockClient::new();
+        mock.expect(
+            Expectation::patch(&["v1", "vaults", "vault", "items", "item"]).respond_json(&json!({
+                "id": "item",
+                "title": "Login",
+                "category": "LOGIN",
+                "vault": { "id": "vault" },
+                "fields": [],
+            })),
+        );
+
+        let patch = ItemPatch::new()
+            .title("Login")
+            .field_value("password", "hunter2");
+        let recorder = Cassette::record(mock, &path);
+        items::patch(&recorder, "vault", "item", &patch)
+            .await
+            .unwrap();
+        recorder.save().unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(!written.contains("hunter2"));
+        assert!(written.contains("/fields/password/value"));
+        assert!(written.contains("Login"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
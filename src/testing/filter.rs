@@ -0,0 +1,173 @@
+//! Evaluates the SCIM-style `filter` parameter, as compiled by [`Filter`]
+
+use crate::filter::{Filter, Operator};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Value(String),
+    Open,
+    Close,
+}
+
+/// Parse a filter, `and` binds tighter than `or`
+pub(super) fn parse(input: &str) -> Result<Filter, String> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+    };
+
+    let filter = parser.or()?;
+    match parser.next() {
+        None => Ok(filter),
+        Some(token) => Err(format!("unexpected {:?}", token)),
+    }
+}
+
+/// Returns true if any of the values of an attribute, as returned by `values`, matches
+pub(super) fn matches(filter: &Filter, values: impl Fn(&str) -> Vec<String> + Copy) -> bool {
+    match filter {
+        Filter::Compare {
+            attribute,
+            operator,
+            value,
+        } => values(attribute).iter().any(|candidate| match operator {
+            Operator::Eq => candidate == value,
+            Operator::Sw => candidate.starts_with(value.as_str()),
+            Operator::Co => candidate.contains(value.as_str()),
+        }),
+        Filter::And(left, right) => matches(left, values) && matches(right, values),
+        Filter::Or(left, right) => matches(left, values) || matches(right, values),
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => value.push(chars.next().ok_or("unterminated escape")?),
+                        Some('"') => break,
+                        Some(c) => value.push(c),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+                tokens.push(Token::Value(value));
+            }
+            c => {
+                let mut word = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' || c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek_word(&self, word: &str) -> bool {
+        matches!(self.tokens.get(self.position), Some(Token::Word(w)) if w.eq_ignore_ascii_case(word))
+    }
+
+    fn or(&mut self) -> Result<Filter, String> {
+        let mut filter = self.and()?;
+        while self.peek_word("or") {
+            self.position += 1;
+            filter = filter.or(self.and()?);
+        }
+
+        Ok(filter)
+    }
+
+    fn and(&mut self) -> Result<Filter, String> {
+        let mut filter = self.operand()?;
+        while self.peek_word("and") {
+            self.position += 1;
+            filter = filter.and(self.operand()?);
+        }
+
+        Ok(filter)
+    }
+
+    fn operand(&mut self) -> Result<Filter, String> {
+        match self.next() {
+            Some(Token::Open) => {
+                let filter = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(filter),
+                    _ => Err("expected )".to_string()),
+                }
+            }
+            Some(Token::Word(attribute)) => {
+                let operator = match self.next() {
+                    Some(Token::Word(op)) if op.eq_ignore_ascii_case("eq") => Operator::Eq,
+                    Some(Token::Word(op)) if op.eq_ignore_ascii_case("sw") => Operator::Sw,
+                    Some(Token::Word(op)) if op.eq_ignore_ascii_case("co") => Operator::Co,
+                    token => return Err(format!("expected an operator, found {:?}", token)),
+                };
+                match self.next() {
+                    Some(Token::Value(value)) => {
+                        Filter::new(&attribute, operator, &value).map_err(|err| err.to_string())
+                    }
+                    token => Err(format!("expected a quoted value, found {:?}", token)),
+                }
+            }
+            token => Err(format!("expected a comparison, found {:?}", token)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{matches, parse};
+    use crate::filter::Filter;
+
+    #[test]
+    fn parses_compiled_filters() {
+        let filter = Filter::title_eq(r#"say "hi" \o/"#)
+            .or(Filter::title_eq("b"))
+            .and(Filter::tag_eq("prod"));
+
+        assert_eq!(parse(&filter.to_string()).unwrap(), filter);
+    }
+
+    #[test]
+    fn matches_any_value() {
+        let filter = parse(r#"tag eq "prod" and title sw "Dell""#).unwrap();
+        let values = |attribute: &str| match attribute {
+            "tag" => vec!["dev".to_string(), "prod".to_string()],
+            "title" => vec!["Dell XYZ".to_string()],
+            _ => vec![],
+        };
+
+        assert!(matches(&filter, values));
+        assert!(parse("title eq").is_err());
+    }
+}
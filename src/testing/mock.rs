@@ -0,0 +1,376 @@
+//! Mock client with scripted responses
+
+use crate::client::{ConnectRequest, ConnectResponse, HTTPClient, Method};
+use crate::error::{CustomError, Error};
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::{header::CONTENT_TYPE, HeaderMap, HeaderValue, StatusCode};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    fmt,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+type Matcher = Arc<dyn Fn(&ConnectRequest) -> bool + Send + Sync>;
+
+/// Represents an [`HTTPClient`] answering requests with scripted responses, without any network.
+///
+/// Each request is answered by the first queued [`Expectation`] it matches, which is then used
+/// up. Every request is recorded, matched or not, so tests can assert on exactly what was sent.
+/// Clones share their expectations and recorded calls.
+///
+/// ```
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), connect_1password::error::Error> {
+/// use connect_1password::{testing::{Expectation, MockClient}, vaults};
+/// use serde_json::json;
+///
+/// let client = MockClient::new();
+/// client.expect(Expectation::get(&["v1", "vaults", "abc"]).respond_json(&json!({
+///     "id": "abc",
+///     "name": "Production",
+///     "attributeVersion": 1,
+///     "contentVersion": 1,
+///     "type": "USER_CREATED",
+/// })));
+///
+/// let (vault, _) = vaults::get(&client, "abc").await?;
+///
+/// assert_eq!(vault.name, "Production");
+/// assert_eq!(client.calls().len(), 1);
+/// client.verify();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockClient {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    expectations: Vec<Expectation>,
+    calls: Vec<ConnectRequest>,
+}
+
+impl MockClient {
+    /// Create a new instance without any expectations
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue an expected request and its response.
+    pub fn expect(&self, expectation: Expectation) -> &Self {
+        self.inner().expectations.push(expectation);
+        self
+    }
+
+    /// Returns every request received so far, in order.
+    pub fn calls(&self) -> Vec<ConnectRequest> {
+        self.inner().calls.clone()
+    }
+
+    /// Returns true if every queued expectation has been used up.
+    pub fn is_done(&self) -> bool {
+        self.inner().expectations.is_empty()
+    }
+
+    /// Assert every queued expectation has been used up.
+    ///
+    /// # Panics
+    ///
+    /// Panics, listing the remaining expectations, if any were not matched.
+    pub fn verify(&self) {
+        let inner = self.inner();
+        if !inner.expectations.is_empty() {
+            panic!(
+                "{} expected request(s) were not received: {:#?}",
+                inner.expectations.len(),
+                inner.expectations
+            );
+        }
+    }
+
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().expect("mock client state")
+    }
+}
+
+#[async_trait]
+impl HTTPClient for MockClient {
+    async fn execute(&self, request: ConnectRequest) -> Result<ConnectResponse, Error> {
+        let mut inner = self.inner();
+        inner.calls.push(request.clone());
+
+        let position = inner
+            .expectations
+            .iter()
+            .position(|expectation| expectation.matches(&request));
+        match position {
+            Some(position) => Ok(inner.expectations.remove(position).response()),
+            None => Err(CustomError::new(&format!(
+                "No expectation matches {} {}",
+                request.method(),
+                request.path_and_query()
+            ))
+            .into()),
+        }
+    }
+}
+
+/// This is an expected request together with the response to send back.
+///
+/// Without a response set, matching requests are answered with `200 OK` and an empty JSON object.
+#[derive(Clone)]
+pub struct Expectation {
+    method: Option<Method>,
+    segments: Option<Vec<String>>,
+    query: Vec<(String, String)>,
+    body: Option<Value>,
+    matchers: Vec<Matcher>,
+    status: StatusCode,
+    headers: HeaderMap,
+    response: Bytes,
+}
+
+impl fmt::Debug for Expectation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Expectation")
+            .field("method", &self.method)
+            .field("segments", &self.segments)
+            .field("query", &self.query)
+            .field("body", &self.body)
+            .field("matchers", &self.matchers.len())
+            .field("status", &self.status)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Expectation {
+    /// Expect any request
+    pub fn any() -> Self {
+        Self {
+            method: None,
+            segments: None,
+            query: vec![],
+            body: None,
+            matchers: vec![],
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            response: Bytes::from_static(b"{}"),
+        }
+    }
+
+    /// Expect a request for the path made up of `segments`
+    pub fn new(method: Method, segments: &[&str]) -> Self {
+        Self {
+            method: Some(method),
+            segments: Some(segments.iter().map(|segment| segment.to_string()).collect()),
+            ..Self::any()
+        }
+    }
+
+    /// Expect a `GET` request
+    pub fn get(segments: &[&str]) -> Self {
+        Self::new(Method::Get, segments)
+    }
+
+    /// Expect a `POST` request
+    pub fn post(segments: &[&str]) -> Self {
+        Self::new(Method::Post, segments)
+    }
+
+    /// Expect a `PUT` request
+    pub fn put(segments: &[&str]) -> Self {
+        Self::new(Method::Put, segments)
+    }
+
+    /// Expect a `PATCH` request
+    pub fn patch(segments: &[&str]) -> Self {
+        Self::new(Method::Patch, segments)
+    }
+
+    /// Expect a `DELETE` request
+    pub fn delete(segments: &[&str]) -> Self {
+        Self::new(Method::Delete, segments)
+    }
+
+    /// Only match requests with this query parameter.
+    pub fn query(mut self, key: &str, value: &str) -> Self {
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Only match requests whose body is equal to `body` once both are decoded as JSON.
+    pub fn body_json<T: Serialize + ?Sized>(mut self, body: &T) -> Self {
+        self.body = Some(serde_json::to_value(body).expect("expected body serializes as JSON"));
+        self
+    }
+
+    /// Only match requests for which `matcher` returns true.
+    pub fn matching(
+        mut self,
+        matcher: impl Fn(&ConnectRequest) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.matchers.push(Arc::new(matcher));
+        self
+    }
+
+    /// Respond with `status` and `body`.
+    pub fn respond(mut self, status: StatusCode, body: impl Into<Bytes>) -> Self {
+        self.status = status;
+        self.response = body.into();
+        self
+    }
+
+    /// Respond with `200 OK` and `body` serialized as JSON.
+    pub fn respond_json<T: Serialize + ?Sized>(self, body: &T) -> Self {
+        let body = serde_json::to_vec(body).expect("response body serializes as JSON");
+
+        self.respond(StatusCode::OK, body).json_content_type()
+    }
+
+    /// Respond with a Connect error body, i.e. `{"status": 404, "message": "..."}`.
+    pub fn respond_error(self, status: StatusCode, message: &str) -> Self {
+        let body = serde_json::json!({ "status": status.as_u16(), "message": message });
+
+        self.respond(status, body.to_string()).json_content_type()
+    }
+
+    /// Add a response header.
+    pub fn respond_header(mut self, name: http::header::HeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    fn json_content_type(mut self) -> Self {
+        self.headers
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        self
+    }
+
+    fn matches(&self, request: &ConnectRequest) -> bool {
+        self.method
+            .map_or(true, |method| method == request.method())
+            && self
+                .segments
+                .as_ref()
+                .map_or(true, |segments| segments.as_slice() == request.segments())
+            && self
+                .query
+                .iter()
+                .all(|pair| request.query_pairs().contains(pair))
+            && self.body.as_ref().map_or(true, |expected| {
+                request
+                    .body_bytes()
+                    .and_then(|body| serde_json::from_slice::<Value>(body).ok())
+                    .is_some_and(|body| &body == expected)
+            })
+            && self.matchers.iter().all(|matcher| matcher(request))
+    }
+
+    fn response(self) -> ConnectResponse {
+        let mut response = ConnectResponse::new(self.status, self.response);
+        response.headers = self.headers;
+
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Expectation, MockClient};
+    use crate::{
+        client::{HTTPClient, Method},
+        items,
+        models::item::{FullItem, ItemBuilder, ItemCategory, LoginItem},
+    };
+    use http::StatusCode;
+    use serde_json::json;
+    use tokio::test;
+
+    fn login() -> FullItem {
+        ItemBuilder::new("vault", ItemCategory::Login)
+            .title("Login")
+            .username("Bob")
+            .password("")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    async fn records_sent_requests() {
+        let client = MockClient::new();
+        let item = login();
+        let body = serde_json::to_value(&item).unwrap();
+        client.expect(
+            Expectation::post(&["v1", "vaults", "vault", "items"])
+                .body_json(&body)
+                .respond_json(&json!({
+                    "id": "new",
+                    "title": "Login",
+                    "category": "LOGIN",
+                    "vault": { "id": "vault" },
+                })),
+        );
+
+        let (new_item, _) = items::add(&client, item).await.unwrap();
+        assert_eq!(new_item.id, "new");
+
+        let calls = client.calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].method(), Method::Post);
+        client.verify();
+    }
+
+    #[test]
+    async fn answers_with_errors() {
+        let client = MockClient::new();
+        client.expect(
+            Expectation::get(&["v1", "vaults", "vault", "items", "missing"])
+                .respond_error(StatusCode::NOT_FOUND, "Item missing not found"),
+        );
+
+        let err = items::get(&client, "vault", "missing", false)
+            .await
+            .unwrap_err();
+
+        assert!(err.is_not_found());
+    }
+
+    #[test]
+    async fn rejects_unexpected_requests() {
+        let client = MockClient::new();
+        client.expect(Expectation::get(&["v1", "vaults"]).query("filter", "name eq \"x\""));
+
+        assert!(client
+            .send_request::<serde_json::Value>(crate::client::ConnectRequest::get(&[
+                "v1", "vaults"
+            ]))
+            .await
+            .is_err());
+        assert!(!client.is_done());
+        assert_eq!(client.calls().len(), 1);
+    }
+
+    #[test]
+    async fn rejects_invalid_paths() {
+        let client = MockClient::new();
+        client.expect(Expectation::any());
+
+        for id in ["", ".."] {
+            assert!(items::get(&client, "vault", id, false).await.is_err());
+        }
+        assert!(client.calls().is_empty());
+    }
+
+    #[should_panic]
+    #[test]
+    async fn verify_reports_unused_expectations() {
+        let client = MockClient::new();
+        client.expect(Expectation::any());
+
+        client.verify();
+    }
+}
@@ -0,0 +1,452 @@
+//! Test doubles, available with the `testing` feature
+//!
+//! Use [`MockClient`] to unit test code calling the API with scripted responses,
+//! [`FakeConnect`] to run against a stateful server, and [`Cassette`] to replay recorded
+//! interactions with a real Connect server.
+//!
+//! [`FakeConnect`] serves the Connect REST API from memory on a random local port, so tests can
+//! point a real client at it instead of a live Connect server. It supports vaults, items CRUD,
+//! filters, file attachments, API activity and the server status endpoints, and answers errors
+//! with the same `{"status": .., "message": ..}` bodies as Connect.
+//!
+//! ```
+//! # #[cfg(feature = "hyper-client")]
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), connect_1password::error::Error> {
+//! use connect_1password::{
+//!     items,
+//!     models::item::{FullItem, ItemBuilder, ItemCategory, LoginItem},
+//!     testing::FakeConnect,
+//! };
+//!
+//! let server = FakeConnect::start();
+//! let vault_id = server.add_vault("Tests");
+//! let client = server.client();
+//!
+//! let item: FullItem = ItemBuilder::new(&vault_id, ItemCategory::Login)
+//!     .title("Secure server login")
+//!     .username("Bob")
+//!     .password("")
+//!     .build()
+//!     .unwrap();
+//! let (new_item, _) = items::add(&client, item).await?;
+//! let (all, _) = items::all(&client, &vault_id).await?;
+//!
+//! assert_eq!(all[0].id, new_item.id);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "hyper-client"))]
+//! # fn main() {}
+//! ```
+
+// `Option::is_none_or`, which clippy suggests instead, needs a newer Rust than we support
+#![allow(clippy::unnecessary_map_or)]
+
+use crate::models::{file::FileObject, item::FullItem};
+use http::StatusCode;
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server,
+};
+use std::{
+    convert::Infallible,
+    net::TcpListener,
+    sync::{Arc, Mutex, MutexGuard},
+    thread::JoinHandle,
+};
+use tokio::sync::oneshot;
+
+mod cassette;
+mod filter;
+mod mock;
+mod state;
+
+pub use cassette::{Cassette, Mode, CASSETTE_ENV, REDACTED};
+pub use mock::{Expectation, MockClient};
+use state::{Reply, State};
+
+/// The API token accepted by a [`FakeConnect`] server.
+pub const FAKE_TOKEN: &str = "fake-connect-token";
+
+/// Represents a fake Connect server, running until it is dropped.
+///
+/// The server runs on its own thread and runtime, so it can be used from async tests as well as
+/// with the [`blocking`](crate::blocking) API.
+#[derive(Debug)]
+pub struct FakeConnect {
+    url: String,
+    state: Arc<Mutex<State>>,
+    shutdown: Option<oneshot::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FakeConnect {
+    /// Start a new, empty, server on a random local port
+    ///
+    /// # Panics
+    ///
+    /// Panics if the server cannot be started.
+    pub fn start() -> Self {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).expect("bind fake Connect server");
+        listener
+            .set_nonblocking(true)
+            .expect("configure fake Connect listener");
+        let url = format!("http://{}", listener.local_addr().expect("local address"));
+
+        let state = Arc::new(Mutex::new(State::new(FAKE_TOKEN)));
+        let (shutdown, stopped) = oneshot::channel::<()>();
+
+        let service_state = state.clone();
+        let thread = std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("fake Connect runtime");
+
+            runtime.block_on(async move {
+                let make_service = make_service_fn(move |_| {
+                    let state = service_state.clone();
+                    async move {
+                        Ok::<_, Infallible>(service_fn(move |req| serve(state.clone(), req)))
+                    }
+                });
+                let server = Server::from_tcp(listener)
+                    .expect("fake Connect listener")
+                    .serve(make_service);
+
+                tokio::select! {
+                    _ = server => {}
+                    _ = stopped => {}
+                }
+            });
+        });
+
+        Self {
+            url,
+            state,
+            shutdown: Some(shutdown),
+            thread: Some(thread),
+        }
+    }
+
+    /// Returns the URL of the server, i.e. `http://127.0.0.1:49152`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the API token accepted by the server.
+    pub fn token(&self) -> &str {
+        FAKE_TOKEN
+    }
+
+    /// Create a [`Client`](crate::client::Client) for this server, available with the
+    /// `hyper-client` feature.
+    #[cfg(feature = "hyper-client")]
+    pub fn client(&self) -> crate::client::Client {
+        crate::client::Client::new(self.token(), self.url())
+    }
+
+    /// Create a [`ReqwestClient`](crate::client::ReqwestClient) for this server, available with
+    /// the `reqwest` feature.
+    #[cfg(feature = "reqwest")]
+    pub fn reqwest_client(&self) -> crate::client::ReqwestClient {
+        crate::client::ReqwestClient::new(self.token(), self.url())
+    }
+
+    /// Add a vault named `name`, returning its UUID.
+    pub fn add_vault(&self, name: &str) -> String {
+        self.state().add_vault(name)
+    }
+
+    /// Add an item, bypassing the API, returning the item as stored by the server.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vault of the item does not exist.
+    pub fn add_item(&self, item: FullItem) -> FullItem {
+        let item = serde_json::to_value(item).expect("serialize item");
+        let stored = self
+            .state()
+            .add_item(item)
+            .expect("vault of the item exists");
+
+        serde_json::from_value(stored).expect("deserialize item")
+    }
+
+    /// Attach a file with `content` to an item, returning its details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the item does not exist.
+    pub fn add_file(
+        &self,
+        vault_id: &str,
+        item_id: &str,
+        name: &str,
+        content: &[u8],
+    ) -> FileObject {
+        self.state()
+            .add_file(vault_id, item_id, name, content)
+            .expect("item exists")
+    }
+
+    /// Answer the next API request with an error, i.e. to test how rate limiting or an outage is
+    /// handled. Queued errors are served in order, one per request, retries included.
+    pub fn fail_next(&self, status: StatusCode, message: &str) {
+        self.state().fail_next(status, message);
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("fake Connect state")
+    }
+}
+
+impl Drop for FakeConnect {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+async fn serve(state: Arc<Mutex<State>>, req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let (parts, body) = req.into_parts();
+
+    let reply = match hyper::body::to_bytes(body).await {
+        Ok(body) => state
+            .lock()
+            .expect("fake Connect state")
+            .handle(&parts, &body),
+        Err(_) => Reply::error(StatusCode::BAD_REQUEST, "Unable to read request body"),
+    };
+
+    Ok(reply.into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FakeConnect;
+    use crate::{
+        client::{ConnectRequest, HTTPClient, NoRetry},
+        error::ConnectErrorCode,
+        files,
+        filter::Filter,
+        items,
+        models::{
+            item::{FullItem, ItemBuilder, ItemCategory, LoginItem},
+            patch::ItemPatch,
+        },
+        vaults,
+    };
+    use futures::TryStreamExt;
+    use http::StatusCode;
+    use tokio::test;
+
+    #[cfg(feature = "hyper-client")]
+    fn client_with_token(server: &FakeConnect, token: &str) -> impl HTTPClient {
+        crate::client::ClientBuilder::new(token, server.url())
+            .retry_policy(NoRetry)
+            .build()
+            .unwrap()
+    }
+
+    #[cfg(not(feature = "hyper-client"))]
+    fn client_with_token(server: &FakeConnect, token: &str) -> impl HTTPClient {
+        crate::client::ReqwestClient::new(token, server.url()).retry_policy(NoRetry)
+    }
+
+    fn client(server: &FakeConnect) -> impl HTTPClient {
+        client_with_token(server, server.token())
+    }
+
+    fn login(vault_id: &str, title: &str) -> FullItem {
+        ItemBuilder::new(vault_id, ItemCategory::Login)
+            .title(title)
+            .username("Bob")
+            .password("secret")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    async fn filters_vaults() {
+        let server = FakeConnect::start();
+        server.add_vault("Production");
+        let staging = server.add_vault("Staging");
+        let client = client(&server);
+
+        let (found, _) = vaults::all_filtered(&client, &Filter::name_eq("Staging"))
+            .await
+            .unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, staging);
+    }
+
+    #[test]
+    async fn rejects_invalid_tokens() {
+        let server = FakeConnect::start();
+        let client = client_with_token(&server, "wrong");
+
+        let err = vaults::all(&client).await.unwrap_err();
+
+        assert!(err.is_unauthorized());
+    }
+
+    #[test]
+    async fn reports_missing_items() {
+        let server = FakeConnect::start();
+        let vault_id = server.add_vault("Tests");
+        let client = client(&server);
+
+        let err = items::get(&client, &vault_id, "missing", false)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.connect_error().map(|err| err.code),
+            Some(ConnectErrorCode::ItemNotFound)
+        );
+
+        let err = vaults::get(&client, "missing").await.unwrap_err();
+        assert_eq!(
+            err.connect_error().map(|err| err.code),
+            Some(ConnectErrorCode::VaultNotFound)
+        );
+    }
+
+    #[test]
+    async fn filters_items_by_title_and_tag() {
+        let server = FakeConnect::start();
+        let vault_id = server.add_vault("Tests");
+        let client = client(&server);
+
+        let (a, _) = items::add(&client, login(&vault_id, "Alpha"))
+            .await
+            .unwrap();
+        let (b, _) = items::add(&client, login(&vault_id, "Beta")).await.unwrap();
+        items::patch(&client, &vault_id, &b.id, &ItemPatch::new().tags(&["prod"]))
+            .await
+            .unwrap();
+
+        let filter = Filter::title_eq("Alpha").or(Filter::tag_eq("prod"));
+        let (found, _) = items::all_filtered(&client, &vault_id, &filter)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 2);
+
+        let filter = Filter::title_eq("Alpha").and(Filter::tag_eq("prod"));
+        let (found, _) = items::all_filtered(&client, &vault_id, &filter)
+            .await
+            .unwrap();
+        assert!(found.is_empty());
+
+        let (found, _) = items::all_filtered(&client, &vault_id, &Filter::title_eq("Beta"))
+            .await
+            .unwrap();
+        assert_eq!(found[0].id, b.id);
+        assert_ne!(a.id, b.id);
+    }
+
+    #[test]
+    async fn detects_version_conflicts() {
+        let server = FakeConnect::start();
+        let vault_id = server.add_vault("Tests");
+        let client = client(&server);
+
+        let (item, _) = items::add(&client, login(&vault_id, "Item")).await.unwrap();
+        let (mut stale, _) = items::get(&client, &vault_id, &item.id, false)
+            .await
+            .unwrap();
+        items::patch(
+            &client,
+            &vault_id,
+            &item.id,
+            &ItemPatch::new().title("Changed"),
+        )
+        .await
+        .unwrap();
+
+        stale.title = "Stale".to_string();
+        let err = items::update(&client, stale).await.unwrap_err();
+
+        assert!(err.is_version_conflict());
+    }
+
+    #[test]
+    async fn patches_fields_by_id() {
+        let server = FakeConnect::start();
+        let vault_id = server.add_vault("Tests");
+        let client = client(&server);
+
+        let (item, _) = items::add(&client, login(&vault_id, "Item")).await.unwrap();
+        let patch = ItemPatch::new().field_value("password", "changed");
+        let (patched, _) = items::patch(&client, &vault_id, &item.id, &patch)
+            .await
+            .unwrap();
+
+        let password = patched
+            .fields
+            .iter()
+            .find(|field| field.purpose.as_deref() == Some("PASSWORD"))
+            .unwrap();
+        assert_eq!(password.value.as_deref(), Some("changed"));
+        assert_eq!(patched.version, Some(2));
+    }
+
+    #[test]
+    async fn serves_files() {
+        let server = FakeConnect::start();
+        let vault_id = server.add_vault("Tests");
+        let item = server.add_item(login(&vault_id, "Item"));
+        let item_id = item.id.unwrap();
+        let file = server.add_file(&vault_id, &item_id, "notes.txt", b"hello");
+        let client = client(&server);
+
+        let (item, _) = items::get(&client, &vault_id, &item_id, true)
+            .await
+            .unwrap();
+        let inline = &item.files.unwrap()[0];
+        assert_eq!(inline.decoded_content().unwrap().unwrap(), b"hello");
+
+        let content: Vec<u8> = files::content(&client, &vault_id, &item_id, &file.id)
+            .await
+            .unwrap()
+            .try_fold(Vec::new(), |mut acc, chunk| async move {
+                acc.extend_from_slice(&chunk);
+                Ok(acc)
+            })
+            .await
+            .unwrap();
+        assert_eq!(content, b"hello");
+    }
+
+    #[test]
+    async fn serves_queued_failures() {
+        let server = FakeConnect::start();
+        server.add_vault("Tests");
+        server.fail_next(StatusCode::TOO_MANY_REQUESTS, "Too many requests");
+        let client = client(&server);
+
+        let err = vaults::all(&client).await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::TOO_MANY_REQUESTS));
+        assert!(err.is_retryable());
+
+        assert_eq!(vaults::all(&client).await.unwrap().0.len(), 1);
+    }
+
+    #[test]
+    async fn rejects_unknown_paths() {
+        let server = FakeConnect::start();
+        let client = client(&server);
+
+        let response = client
+            .execute(ConnectRequest::get(&["v1", "nope"]))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+    }
+}
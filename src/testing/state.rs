@@ -0,0 +1,990 @@
+//! In-memory state and request handling of the fake server
+
+use super::filter;
+use crate::models::file::FileObject;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use bytes::Bytes;
+use chrono::Utc;
+use http::{header::CONTENT_TYPE, request::Parts, HeaderValue, Method, StatusCode};
+use hyper::{Body, Response};
+use percent_encoding::percent_decode_str;
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, VecDeque};
+use uuid::Uuid;
+
+const ID_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+const PASSWORD_ALPHABET: &[u8] =
+    b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!@#$%^&*";
+const DEFAULT_ACTIVITY_LIMIT: usize = 50;
+
+/// This is a response of the fake server
+#[derive(Debug)]
+pub(super) struct Reply {
+    status: StatusCode,
+    content_type: &'static str,
+    body: Bytes,
+}
+
+impl Reply {
+    fn json(status: StatusCode, value: &Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: Bytes::from(value.to_string()),
+        }
+    }
+
+    fn ok(value: &Value) -> Self {
+        Self::json(StatusCode::OK, value)
+    }
+
+    fn text(body: impl Into<Bytes>) -> Self {
+        Self {
+            status: StatusCode::OK,
+            content_type: "text/plain",
+            body: body.into(),
+        }
+    }
+
+    /// Create an error reply with a Connect error body
+    pub(super) fn error(status: StatusCode, message: &str) -> Self {
+        Self::json(
+            status,
+            &json!({ "status": status.as_u16(), "message": message }),
+        )
+    }
+
+    pub(super) fn into_response(self) -> Response<Body> {
+        let mut response = Response::new(Body::from(self.body));
+        *response.status_mut() = self.status;
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(self.content_type));
+
+        response
+    }
+}
+
+#[derive(Debug)]
+struct Vault {
+    data: Value,
+    items: Vec<Item>,
+}
+
+#[derive(Debug)]
+struct Item {
+    data: Value,
+    files: Vec<File>,
+}
+
+#[derive(Debug)]
+struct File {
+    object: FileObject,
+    content: Vec<u8>,
+}
+
+/// Describes the resource a request acted upon, for the activity log
+struct Resource<'a> {
+    vault_id: &'a str,
+    item: Option<(&'a str, Option<u64>)>,
+}
+
+/// Defines the data served by the fake server
+#[derive(Debug)]
+pub(super) struct State {
+    token: String,
+    vaults: Vec<Vault>,
+    activity: Vec<Value>,
+    failures: VecDeque<(StatusCode, String)>,
+}
+
+impl State {
+    pub(super) fn new(token: &str) -> Self {
+        Self {
+            token: token.to_string(),
+            vaults: vec![],
+            activity: vec![],
+            failures: VecDeque::new(),
+        }
+    }
+
+    pub(super) fn add_vault(&mut self, name: &str) -> String {
+        let id = self.random_string(ID_ALPHABET, 26);
+        let now = now();
+
+        self.vaults.push(Vault {
+            data: json!({
+                "id": id,
+                "name": name,
+                "attributeVersion": 1,
+                "contentVersion": 1,
+                "items": 0,
+                "type": "USER_CREATED",
+                "createdAt": now,
+                "updatedAt": now,
+            }),
+            items: vec![],
+        });
+
+        id
+    }
+
+    /// Store a new item, returns `None` if its vault does not exist
+    pub(super) fn add_item(&mut self, mut item: Value) -> Option<Value> {
+        let vault_id = item["vault"]["id"].as_str()?.to_string();
+        self.vault(&vault_id)?;
+
+        let now = now();
+        item["id"] = Value::from(self.random_string(ID_ALPHABET, 26));
+        item["version"] = Value::from(1);
+        item["createdAt"] = Value::from(now.clone());
+        item["updatedAt"] = Value::from(now);
+        self.normalize(&mut item);
+
+        let vault = self.vault_mut(&vault_id)?;
+        vault.items.push(Item {
+            data: item.clone(),
+            files: vec![],
+        });
+        touch(vault);
+
+        Some(item)
+    }
+
+    pub(super) fn add_file(
+        &mut self,
+        vault_id: &str,
+        item_id: &str,
+        name: &str,
+        content: &[u8],
+    ) -> Option<FileObject> {
+        let id = self.random_string(ID_ALPHABET, 26);
+        let item = self.item_mut(vault_id, item_id)?;
+
+        let object = FileObject {
+            content_path: format!(
+                "/v1/vaults/{}/items/{}/files/{}/content",
+                vault_id, item_id, id
+            ),
+            id,
+            name: name.to_string(),
+            size: content.len() as u64,
+            content: None,
+            section: None,
+        };
+        item.files.push(File {
+            object: object.clone(),
+            content: content.to_vec(),
+        });
+
+        Some(object)
+    }
+
+    pub(super) fn fail_next(&mut self, status: StatusCode, message: &str) {
+        self.failures.push_back((status, message.to_string()));
+    }
+
+    /// Answer a request
+    pub(super) fn handle(&mut self, parts: &Parts, body: &[u8]) -> Reply {
+        let segments: Vec<String> = parts
+            .uri
+            .path()
+            .trim_matches('/')
+            .split('/')
+            .map(|segment| percent_decode_str(segment).decode_utf8_lossy().to_string())
+            .collect();
+        let query = parse_query(parts.uri.query().unwrap_or_default());
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+        match (&parts.method, segments.as_slice()) {
+            (&Method::GET, ["heartbeat"]) => return Reply::text("."),
+            (&Method::GET, ["health"]) => return Reply::ok(&health()),
+            (&Method::GET, ["metrics"]) => return Reply::text(self.metrics()),
+            _ => {}
+        }
+
+        let authorized = parts
+            .headers
+            .get(http::header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| token == self.token);
+        if !authorized {
+            return Reply::error(StatusCode::UNAUTHORIZED, "Invalid bearer token");
+        }
+
+        if let Some((status, message)) = self.failures.pop_front() {
+            return Reply::error(status, &message);
+        }
+
+        let method = &parts.method;
+        match segments.as_slice() {
+            ["v1", "activity"] if method == Method::GET => self.list_activity(&query),
+            ["v1", "vaults"] if method == Method::GET => self.list_vaults(&query),
+            ["v1", "vaults", vault_id] if method == Method::GET => self.get_vault(vault_id),
+            ["v1", "vaults", vault_id, "items"] => match *method {
+                Method::GET => self.list_items(vault_id, &query),
+                Method::POST => self.create_item(vault_id, body),
+                _ => method_not_allowed(),
+            },
+            ["v1", "vaults", vault_id, "items", item_id] => match *method {
+                Method::GET => self.get_item(vault_id, item_id, &query),
+                Method::PUT => self.replace_item(vault_id, item_id, body),
+                Method::PATCH => self.patch_item(vault_id, item_id, body),
+                Method::DELETE => self.delete_item(vault_id, item_id),
+                _ => method_not_allowed(),
+            },
+            ["v1", "vaults", vault_id, "items", item_id, "files"] if method == Method::GET => {
+                self.list_files(vault_id, item_id, &query)
+            }
+            ["v1", "vaults", vault_id, "items", item_id, "files", file_id]
+                if method == Method::GET =>
+            {
+                self.get_file(vault_id, item_id, file_id, &query)
+            }
+            ["v1", "vaults", vault_id, "items", item_id, "files", file_id, "content"]
+                if method == Method::GET =>
+            {
+                self.file_content(vault_id, item_id, file_id)
+            }
+            _ => Reply::error(StatusCode::NOT_FOUND, "Resource not found"),
+        }
+    }
+
+    fn list_vaults(&mut self, query: &HashMap<String, String>) -> Reply {
+        let filter = match parse_filter(query) {
+            Ok(filter) => filter,
+            Err(reply) => return reply,
+        };
+
+        let vaults: Vec<Value> = self
+            .vaults
+            .iter()
+            .map(|vault| vault.data.clone())
+            .filter(|vault| {
+                filter.as_ref().map_or(true, |filter| {
+                    filter::matches(filter, |attribute| match attribute {
+                        "name" => string_values(&vault["name"]),
+                        _ => vec![],
+                    })
+                })
+            })
+            .collect();
+        self.log("READ", None);
+
+        Reply::ok(&Value::from(vaults))
+    }
+
+    fn get_vault(&mut self, vault_id: &str) -> Reply {
+        let data = match self.vault(vault_id) {
+            Some(vault) => vault.data.clone(),
+            None => return vault_not_found(vault_id),
+        };
+        self.log(
+            "READ",
+            Some(Resource {
+                vault_id,
+                item: None,
+            }),
+        );
+
+        Reply::ok(&data)
+    }
+
+    fn list_items(&mut self, vault_id: &str, query: &HashMap<String, String>) -> Reply {
+        let filter = match parse_filter(query) {
+            Ok(filter) => filter,
+            Err(reply) => return reply,
+        };
+        let vault = match self.vault(vault_id) {
+            Some(vault) => vault,
+            None => return vault_not_found(vault_id),
+        };
+
+        let items: Vec<Value> = vault
+            .items
+            .iter()
+            .filter(|item| {
+                filter.as_ref().map_or(true, |filter| {
+                    filter::matches(filter, |attribute| match attribute {
+                        "title" => string_values(&item.data["title"]),
+                        "tag" => string_values(&item.data["tags"]),
+                        _ => vec![],
+                    })
+                })
+            })
+            .map(|item| summary(&item.data))
+            .collect();
+        self.log(
+            "READ",
+            Some(Resource {
+                vault_id,
+                item: None,
+            }),
+        );
+
+        Reply::ok(&Value::from(items))
+    }
+
+    fn create_item(&mut self, vault_id: &str, body: &[u8]) -> Reply {
+        let item = match parse_item(body) {
+            Ok(item) => item,
+            Err(reply) => return reply,
+        };
+        if self.vault(vault_id).is_none() {
+            return vault_not_found(vault_id);
+        }
+        if item["vault"]["id"].as_str() != Some(vault_id) {
+            return Reply::error(
+                StatusCode::BAD_REQUEST,
+                "Validation: vault of the item does not match the request path",
+            );
+        }
+
+        let item = match self.add_item(item) {
+            Some(item) => item,
+            None => return vault_not_found(vault_id),
+        };
+        let item_id = item["id"].as_str().unwrap_or_default().to_string();
+        self.log(
+            "CREATE",
+            Some(Resource {
+                vault_id,
+                item: Some((&item_id, Some(1))),
+            }),
+        );
+
+        Reply::ok(&item)
+    }
+
+    fn get_item(
+        &mut self,
+        vault_id: &str,
+        item_id: &str,
+        query: &HashMap<String, String>,
+    ) -> Reply {
+        let inline = inline_files(query);
+        let (data, version) = match self.find_item(vault_id, item_id) {
+            Ok(item) => (full(item, inline), item.data["version"].as_u64()),
+            Err(reply) => return reply,
+        };
+        self.log(
+            "READ",
+            Some(Resource {
+                vault_id,
+                item: Some((item_id, version)),
+            }),
+        );
+
+        Reply::ok(&data)
+    }
+
+    fn replace_item(&mut self, vault_id: &str, item_id: &str, body: &[u8]) -> Reply {
+        let mut replacement = match parse_item(body) {
+            Ok(item) => item,
+            Err(reply) => return reply,
+        };
+        if replacement["id"].as_str() != Some(item_id)
+            || replacement["vault"]["id"].as_str() != Some(vault_id)
+        {
+            return Reply::error(
+                StatusCode::BAD_REQUEST,
+                "Validation: item and vault UUIDs must match the request path",
+            );
+        }
+
+        let current = match self.find_item(vault_id, item_id) {
+            Ok(item) => item.data.clone(),
+            Err(reply) => return reply,
+        };
+        let version = current["version"].as_u64().unwrap_or(1);
+        if let Some(expected) = replacement["version"].as_u64() {
+            if expected != version {
+                return version_conflict(item_id, expected, version);
+            }
+        }
+
+        replacement["version"] = Value::from(version + 1);
+        replacement["createdAt"] = current["createdAt"].clone();
+        replacement["updatedAt"] = Value::from(now());
+        self.store(vault_id, item_id, replacement, "UPDATE")
+    }
+
+    fn patch_item(&mut self, vault_id: &str, item_id: &str, body: &[u8]) -> Reply {
+        let operations: Vec<Value> = match serde_json::from_slice(body) {
+            Ok(operations) => operations,
+            Err(_) => return Reply::error(StatusCode::BAD_REQUEST, "Invalid patch document"),
+        };
+        let mut item = match self.find_item(vault_id, item_id) {
+            Ok(item) => item.data.clone(),
+            Err(reply) => return reply,
+        };
+
+        for operation in &operations {
+            if let Err(message) = apply_patch(&mut item, operation) {
+                return Reply::error(StatusCode::BAD_REQUEST, &message);
+            }
+        }
+
+        let version = item["version"].as_u64().unwrap_or(1);
+        item["id"] = Value::from(item_id);
+        item["vault"] = json!({ "id": vault_id });
+        item["version"] = Value::from(version + 1);
+        item["updatedAt"] = Value::from(now());
+        self.store(vault_id, item_id, item, "UPDATE")
+    }
+
+    fn delete_item(&mut self, vault_id: &str, item_id: &str) -> Reply {
+        let version = match self.find_item(vault_id, item_id) {
+            Ok(item) => item.data["version"].as_u64(),
+            Err(reply) => return reply,
+        };
+        if let Some(vault) = self.vault_mut(vault_id) {
+            vault.items.retain(|item| item.data["id"] != item_id);
+            touch(vault);
+        }
+        self.log(
+            "DELETE",
+            Some(Resource {
+                vault_id,
+                item: Some((item_id, version)),
+            }),
+        );
+
+        Reply {
+            status: StatusCode::NO_CONTENT,
+            content_type: "application/json",
+            body: Bytes::new(),
+        }
+    }
+
+    fn list_files(
+        &mut self,
+        vault_id: &str,
+        item_id: &str,
+        query: &HashMap<String, String>,
+    ) -> Reply {
+        let inline = inline_files(query);
+
+        match self.find_item(vault_id, item_id) {
+            Ok(item) => {
+                let files: Vec<Value> = item
+                    .files
+                    .iter()
+                    .map(|file| file_json(file, inline))
+                    .collect();
+                Reply::ok(&Value::from(files))
+            }
+            Err(reply) => reply,
+        }
+    }
+
+    fn get_file(
+        &mut self,
+        vault_id: &str,
+        item_id: &str,
+        file_id: &str,
+        query: &HashMap<String, String>,
+    ) -> Reply {
+        let inline = inline_files(query);
+
+        match self.find_file(vault_id, item_id, file_id) {
+            Ok(file) => Reply::ok(&file_json(file, inline)),
+            Err(reply) => reply,
+        }
+    }
+
+    fn file_content(&mut self, vault_id: &str, item_id: &str, file_id: &str) -> Reply {
+        match self.find_file(vault_id, item_id, file_id) {
+            Ok(file) => Reply {
+                status: StatusCode::OK,
+                content_type: "application/octet-stream",
+                body: Bytes::from(file.content.clone()),
+            },
+            Err(reply) => reply,
+        }
+    }
+
+    fn list_activity(&mut self, query: &HashMap<String, String>) -> Reply {
+        let number = |key: &str, default: usize| {
+            query
+                .get(key)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(default)
+        };
+        let limit = number("limit", DEFAULT_ACTIVITY_LIMIT);
+        let offset = number("offset", 0);
+
+        let requests: Vec<Value> = self
+            .activity
+            .iter()
+            .rev()
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect();
+
+        Reply::ok(&Value::from(requests))
+    }
+
+    fn metrics(&self) -> String {
+        let items: usize = self.vaults.iter().map(|vault| vault.items.len()).sum();
+
+        format!(
+            "# HELP connect_vaults Number of vaults.\n\
+             # TYPE connect_vaults gauge\n\
+             connect_vaults {}\n\
+             # HELP connect_items Number of items.\n\
+             # TYPE connect_items gauge\n\
+             connect_items {}\n\
+             # HELP connect_api_requests_total Number of API requests.\n\
+             # TYPE connect_api_requests_total counter\n\
+             connect_api_requests_total {}\n",
+            self.vaults.len(),
+            items,
+            self.activity.len()
+        )
+    }
+
+    /// Replace a stored item after an update, keeping its attached files
+    fn store(&mut self, vault_id: &str, item_id: &str, mut data: Value, action: &str) -> Reply {
+        self.normalize(&mut data);
+        let version = data["version"].as_u64();
+
+        let item = match self.item_mut(vault_id, item_id) {
+            Some(item) => item,
+            None => return item_not_found(item_id),
+        };
+        item.data = data;
+        let reply = full(item, false);
+        if let Some(vault) = self.vault_mut(vault_id) {
+            touch(vault);
+        }
+        self.log(
+            action,
+            Some(Resource {
+                vault_id,
+                item: Some((item_id, version)),
+            }),
+        );
+
+        Reply::ok(&reply)
+    }
+
+    /// Assign IDs to new fields and sections and generate requested values, as Connect does
+    fn normalize(&self, item: &mut Value) {
+        if let Some(sections) = item["sections"].as_array_mut() {
+            for section in sections {
+                if section["id"].as_str().is_none() {
+                    section["id"] = Value::from(self.random_string(ID_ALPHABET, 26));
+                }
+            }
+        }
+
+        if let Some(fields) = item["fields"].as_array_mut() {
+            for field in fields {
+                if field["id"].as_str().is_none() {
+                    let id = match field["purpose"].as_str() {
+                        Some("USERNAME") => "username".to_string(),
+                        Some("PASSWORD") => "password".to_string(),
+                        Some("NOTES") => "notesPlain".to_string(),
+                        _ => self.random_string(ID_ALPHABET, 26),
+                    };
+                    field["id"] = Value::from(id);
+                }
+
+                let generate = field["generate"].as_bool().unwrap_or_default();
+                let empty = field["value"].as_str().map_or(true, str::is_empty);
+                if generate && empty {
+                    field["value"] = Value::from(self.random_string(PASSWORD_ALPHABET, 32));
+                }
+                if let Some(field) = field.as_object_mut() {
+                    field.remove("generate");
+                }
+            }
+        }
+    }
+
+    fn log(&mut self, action: &str, resource: Option<Resource<'_>>) {
+        let resource = resource.map(|resource| match resource.item {
+            Some((item_id, version)) => json!({
+                "type": "ITEM",
+                "vault": { "id": resource.vault_id },
+                "item": { "id": item_id },
+                "itemVersion": version,
+            }),
+            None => json!({ "type": "VAULT", "vault": { "id": resource.vault_id } }),
+        });
+        let request_id = self.random_string(ID_ALPHABET, 26);
+
+        self.activity.push(json!({
+            "requestId": request_id,
+            "timestamp": now(),
+            "action": action,
+            "result": "SUCCESS",
+            "actor": {
+                "id": "fakeconnecttoken",
+                "account": "fakeconnectaccount",
+                "jti": "fakeconnectjti",
+                "userAgent": "connect-1password",
+                "requestIp": "127.0.0.1",
+            },
+            "resource": resource,
+        }));
+    }
+
+    fn vault(&self, vault_id: &str) -> Option<&Vault> {
+        self.vaults
+            .iter()
+            .find(|vault| vault.data["id"] == vault_id)
+    }
+
+    fn vault_mut(&mut self, vault_id: &str) -> Option<&mut Vault> {
+        self.vaults
+            .iter_mut()
+            .find(|vault| vault.data["id"] == vault_id)
+    }
+
+    fn item_mut(&mut self, vault_id: &str, item_id: &str) -> Option<&mut Item> {
+        self.vault_mut(vault_id)?
+            .items
+            .iter_mut()
+            .find(|item| item.data["id"] == item_id)
+    }
+
+    fn find_item(&self, vault_id: &str, item_id: &str) -> Result<&Item, Reply> {
+        self.vault(vault_id)
+            .ok_or_else(|| vault_not_found(vault_id))?
+            .items
+            .iter()
+            .find(|item| item.data["id"] == item_id)
+            .ok_or_else(|| item_not_found(item_id))
+    }
+
+    fn find_file(&self, vault_id: &str, item_id: &str, file_id: &str) -> Result<&File, Reply> {
+        self.find_item(vault_id, item_id)?
+            .files
+            .iter()
+            .find(|file| file.object.id == file_id)
+            .ok_or_else(|| {
+                Reply::error(
+                    StatusCode::NOT_FOUND,
+                    &format!("File {} not found", file_id),
+                )
+            })
+    }
+
+    fn random_string(&self, alphabet: &[u8], len: usize) -> String {
+        // The last eight bytes of a v4 UUID are random apart from the variant bits
+        std::iter::repeat_with(|| Uuid::new_v4().as_u128() as u64)
+            .flat_map(u64::to_le_bytes)
+            .take(len)
+            .map(|byte| alphabet[byte as usize % alphabet.len()] as char)
+            .collect()
+    }
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339()
+}
+
+/// Bump the content version of a vault after one of its items changed
+fn touch(vault: &mut Vault) {
+    let version = vault.data["contentVersion"].as_u64().unwrap_or_default();
+
+    vault.data["contentVersion"] = Value::from(version + 1);
+    vault.data["items"] = Value::from(vault.items.len());
+    vault.data["updatedAt"] = Value::from(now());
+}
+
+/// The item as listed, without its fields, sections and files
+fn summary(item: &Value) -> Value {
+    let mut summary = item.clone();
+    if let Some(summary) = summary.as_object_mut() {
+        for key in ["fields", "sections", "files"] {
+            summary.remove(key);
+        }
+    }
+
+    summary
+}
+
+/// The item as fetched, including the details of its files
+fn full(item: &Item, inline: bool) -> Value {
+    let mut data = item.data.clone();
+    if !item.files.is_empty() {
+        data["files"] = item
+            .files
+            .iter()
+            .map(|file| file_json(file, inline))
+            .collect();
+    }
+
+    data
+}
+
+fn file_json(file: &File, inline: bool) -> Value {
+    let mut object = file.object.clone();
+    if inline {
+        object.content = Some(STANDARD.encode(&file.content));
+    }
+
+    serde_json::to_value(object).unwrap_or_default()
+}
+
+fn health() -> Value {
+    json!({
+        "name": "1Password Connect API",
+        "version": "1.0.0",
+        "dependencies": [
+            { "service": "sync", "status": "ACTIVE", "message": "Connected" },
+            { "service": "sqlite", "status": "ACTIVE", "message": "Connected" },
+        ],
+    })
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let decode = |part: &str| percent_decode_str(part).decode_utf8_lossy().to_string();
+
+            (decode(key), decode(value))
+        })
+        .collect()
+}
+
+fn parse_filter(query: &HashMap<String, String>) -> Result<Option<crate::filter::Filter>, Reply> {
+    query
+        .get("filter")
+        .map(|filter| filter::parse(filter))
+        .transpose()
+        .map_err(|message| {
+            Reply::error(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid filter: {}", message),
+            )
+        })
+}
+
+fn parse_item(body: &[u8]) -> Result<Value, Reply> {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(item @ Value::Object(_))
+            if item["title"].is_string() && item["vault"]["id"].is_string() =>
+        {
+            Ok(item)
+        }
+        _ => Err(Reply::error(
+            StatusCode::BAD_REQUEST,
+            "Validation: invalid item, title and vault are required",
+        )),
+    }
+}
+
+fn inline_files(query: &HashMap<String, String>) -> bool {
+    query.get("inline_files").map(String::as_str) == Some("true")
+}
+
+fn string_values(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(value) => vec![value.clone()],
+        Value::Array(values) => values
+            .iter()
+            .filter_map(|value| value.as_str().map(str::to_string))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn vault_not_found(vault_id: &str) -> Reply {
+    Reply::error(
+        StatusCode::NOT_FOUND,
+        &format!("Vault {} not found", vault_id),
+    )
+}
+
+fn item_not_found(item_id: &str) -> Reply {
+    Reply::error(
+        StatusCode::NOT_FOUND,
+        &format!("Item {} not found", item_id),
+    )
+}
+
+fn version_conflict(item_id: &str, expected: u64, current: u64) -> Reply {
+    Reply::error(
+        StatusCode::CONFLICT,
+        &format!(
+            "Item {} has been changed, expected version {} but it is at version {}",
+            item_id, expected, current
+        ),
+    )
+}
+
+fn method_not_allowed() -> Reply {
+    Reply::error(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed")
+}
+
+/// Apply a JSON Patch operation, resolving `fields` and `sections` entries by ID as Connect does
+fn apply_patch(item: &mut Value, operation: &Value) -> Result<(), String> {
+    let op = operation["op"]
+        .as_str()
+        .ok_or("Patch operation without op")?;
+    let path = operation["path"]
+        .as_str()
+        .ok_or("Patch operation without path")?;
+    let mut tokens: Vec<String> = path
+        .strip_prefix('/')
+        .ok_or_else(|| format!("Invalid patch path {}", path))?
+        .split('/')
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .collect();
+    let last = tokens
+        .pop()
+        .ok_or_else(|| format!("Invalid patch path {}", path))?;
+
+    let mut parent = &mut *item;
+    for (index, token) in tokens.iter().enumerate() {
+        let by_id = index > 0 && matches!(tokens[index - 1].as_str(), "fields" | "sections");
+        parent = child(parent, token, by_id).ok_or_else(|| format!("Path {} not found", path))?;
+    }
+    let by_id = tokens
+        .last()
+        .is_some_and(|token| matches!(token.as_str(), "fields" | "sections"));
+
+    match parent {
+        Value::Object(map) => apply_to_object(map, op, &last, operation, path),
+        Value::Array(values) => {
+            let value = || {
+                operation
+                    .get("value")
+                    .cloned()
+                    .ok_or("Patch operation without value")
+            };
+            if op == "add" && last == "-" {
+                values.push(value()?);
+                return Ok(());
+            }
+
+            let index = position(values, &last, by_id)
+                .or_else(|| (op == "add").then(|| last.parse().ok()).flatten())
+                .filter(|index| *index < values.len() || op == "add")
+                .ok_or_else(|| format!("Path {} not found", path))?;
+            match op {
+                "add" => values.insert(index.min(values.len()), value()?),
+                "replace" => values[index] = value()?,
+                "remove" => {
+                    values.remove(index);
+                }
+                _ => return Err(format!("Unsupported patch operation {}", op)),
+            }
+
+            Ok(())
+        }
+        _ => Err(format!("Path {} not found", path)),
+    }
+}
+
+fn apply_to_object(
+    map: &mut Map<String, Value>,
+    op: &str,
+    key: &str,
+    operation: &Value,
+    path: &str,
+) -> Result<(), String> {
+    let value = || {
+        operation
+            .get("value")
+            .cloned()
+            .ok_or("Patch operation without value")
+    };
+
+    match op {
+        "add" => {
+            map.insert(key.to_string(), value()?);
+        }
+        "replace" if map.contains_key(key) => {
+            map.insert(key.to_string(), value()?);
+        }
+        "remove" if map.remove(key).is_some() => {}
+        "replace" | "remove" => return Err(format!("Path {} not found", path)),
+        _ => return Err(format!("Unsupported patch operation {}", op)),
+    }
+
+    Ok(())
+}
+
+fn child<'a>(value: &'a mut Value, token: &str, by_id: bool) -> Option<&'a mut Value> {
+    match value {
+        Value::Object(map) => map.get_mut(token),
+        Value::Array(values) => {
+            let index = position(values, token, by_id)?;
+            values.get_mut(index)
+        }
+        _ => None,
+    }
+}
+
+/// Find an array entry by its `id` when `by_id` is set, or by index
+fn position(values: &[Value], token: &str, by_id: bool) -> Option<usize> {
+    by_id
+        .then(|| values.iter().position(|value| value["id"] == token))
+        .flatten()
+        .or_else(|| token.parse().ok().filter(|index| *index < values.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply_patch;
+    use serde_json::json;
+
+    #[test]
+    fn patches_entries_by_id() {
+        let mut item = json!({
+            "title": "Old",
+            "fields": [{ "id": "username", "value": "bob" }, { "id": "password", "value": "x" }],
+        });
+
+        apply_patch(
+            &mut item,
+            &json!({"op": "replace", "path": "/title", "value": "New"}),
+        )
+        .unwrap();
+        apply_patch(
+            &mut item,
+            &json!({"op": "replace", "path": "/fields/password/value", "value": "y"}),
+        )
+        .unwrap();
+        apply_patch(
+            &mut item,
+            &json!({"op": "remove", "path": "/fields/username"}),
+        )
+        .unwrap();
+        apply_patch(
+            &mut item,
+            &json!({"op": "add", "path": "/fields/-", "value": {"id": "otp"}}),
+        )
+        .unwrap();
+
+        assert_eq!(
+            item,
+            json!({
+                "title": "New",
+                "fields": [{ "id": "password", "value": "y" }, { "id": "otp" }],
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_missing_paths() {
+        let mut item = json!({ "fields": [] });
+
+        assert!(apply_patch(&mut item, &json!({"op": "remove", "path": "/fields/x"})).is_err());
+        assert!(apply_patch(
+            &mut item,
+            &json!({"op": "replace", "path": "/title", "value": 1})
+        )
+        .is_err());
+    }
+}
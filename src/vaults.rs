@@ -1,38 +1,32 @@
 //! Vaults
 
-use crate::error::{ConnectAPIError, Error};
+use crate::error::Error;
 use crate::{
-    client::HTTPClient,
-    models::{StatusWrapper, VaultData},
+    client::{ConnectRequest, HTTPClient},
+    filter::Filter,
+    models::VaultData,
 };
 
 /// Get all known vaults
 pub async fn all(client: &impl HTTPClient) -> Result<(Vec<VaultData>, serde_json::Value), Error> {
-    let params = vec![("", "")];
-
-    let result = match client
-        .send_request::<Vec<VaultData>>("GET", "v1/vaults", &params, None)
-        .await
-    {
-        Ok(value) => value,
-        Err(err) => {
-            let op_error = crate::error::process_connect_error_response(err.to_string())?;
-
-            let message = "Invalid bearer token";
-            if err.to_string().contains(message) {
-                let status = StatusWrapper {
-                    status: op_error.status_code.unwrap_or_default(),
-                };
-
-                return Err(Error::new_connect_error(ConnectAPIError::new(
-                    status.into(),
-                    message,
-                )));
-            }
-
-            return Err(Error::new_internal_error().with(err));
-        }
-    };
+    list(client, ConnectRequest::get(&["v1", "vaults"])).await
+}
+
+/// Get all vaults matching a filter, i.e. `Filter::name_eq("vault")`
+pub async fn all_filtered(
+    client: &impl HTTPClient,
+    filter: &Filter,
+) -> Result<(Vec<VaultData>, serde_json::Value), Error> {
+    let request = ConnectRequest::get(&["v1", "vaults"]).query("filter", &filter.to_string());
+
+    list(client, request).await
+}
+
+async fn list(
+    client: &impl HTTPClient,
+    request: ConnectRequest,
+) -> Result<(Vec<VaultData>, serde_json::Value), Error> {
+    let result = client.send_request::<Vec<VaultData>>(request).await?;
 
     Ok(result)
 }
@@ -42,44 +36,9 @@ pub async fn get(
     client: &impl HTTPClient,
     id: &str,
 ) -> Result<(VaultData, serde_json::Value), Error> {
-    let params = vec![("", "")];
-    let path = format!("v1/vaults/{}", id);
-
-    let result = match client
-        .send_request::<VaultData>("GET", &path, &params, None)
-        .await
-    {
-        Ok(value) => value,
-        Err(err) => {
-            let op_error = crate::error::process_connect_error_response(err.to_string())?;
-
-            let mut message = "Invalid bearer token";
-            if err.to_string().contains(message) {
-                let status = StatusWrapper {
-                    status: op_error.status_code.unwrap_or_default(),
-                };
-
-                return Err(Error::new_connect_error(ConnectAPIError::new(
-                    status.into(),
-                    message,
-                )));
-            }
-
-            message = "Invalid Vault UUID";
-            if err.to_string().contains(message) {
-                let status = StatusWrapper {
-                    status: op_error.status_code.unwrap_or_default(),
-                };
-
-                return Err(Error::new_connect_error(ConnectAPIError::new(
-                    status.into(),
-                    message,
-                )));
-            }
-
-            return Err(Error::new_internal_error().with(err));
-        }
-    };
+    let request = ConnectRequest::get(&["v1", "vaults", id]);
+
+    let result = client.send_request::<VaultData>(request).await?;
 
     Ok(result)
 }
@@ -101,6 +60,17 @@ mod test {
         assert_eq!(vaults[0].name, "connect-1password".to_string());
     }
 
+    #[test]
+    async fn all_filtered() {
+        let (client, _test_vault_id) = get_test_client();
+
+        let filter = Filter::name_eq("connect-1password");
+        let (vaults, _) = vaults::all_filtered(&client, &filter).await.unwrap();
+
+        assert_eq!(vaults.len(), 1);
+        assert_eq!(vaults[0].name, "connect-1password".to_string());
+    }
+
     #[test]
     async fn get() {
         let (client, test_vault_id) = get_test_client();
@@ -111,6 +81,16 @@ mod test {
         assert_eq!(vault.name, "connect-1password".to_string());
     }
 
+    #[test]
+    async fn get_rejects_dot_segments() {
+        let (client, _test_vault_id) = get_test_client();
+
+        // Rejected before it is sent, instead of fetching `v1/vaults`
+        let err = vaults::get(&client, "..").await.unwrap_err();
+
+        assert!(err.status().is_none());
+    }
+
     #[should_panic]
     #[test]
     async fn get_vault_details_not_specified() {